@@ -0,0 +1,63 @@
+// Arbiter 默认检查器
+// 调用方式：arbiter_e <输入文件> <选手输出> <标准答案>
+// 结果写入 /tmp/_eval.score：第一行为信息（不超过 100 字符），第二行为得分（满分 10）
+#include <cctype>
+#include <cstdio>
+#include <cstdlib>
+#include <fstream>
+#include <string>
+#include <vector>
+
+static std::vector<std::string> read_lines(const char* path)
+{
+    std::vector<std::string> lines;
+    std::ifstream file(path);
+    std::string line;
+    while (std::getline(file, line)) {
+        // 忽略行末空白字符
+        while (!line.empty() && std::isspace(static_cast<unsigned char>(line.back()))) {
+            line.pop_back();
+        }
+        lines.push_back(line);
+    }
+    // 忽略文末空行
+    while (!lines.empty() && lines.back().empty()) {
+        lines.pop_back();
+    }
+    return lines;
+}
+
+static void quit(const char* info, int score)
+{
+    FILE* result = std::fopen("/tmp/_eval.score", "w");
+    if (result) {
+        std::fprintf(result, "%s\n%d\n", info, score);
+        std::fclose(result);
+    }
+    std::exit(0);
+}
+
+int main(int argc, char* argv[])
+{
+    if (argc < 4) {
+        quit("Checker arguments error.", 0);
+    }
+
+    std::vector<std::string> output = read_lines(argv[2]);
+    std::vector<std::string> expected = read_lines(argv[3]);
+
+    for (size_t i = 0; i < output.size() && i < expected.size(); i++) {
+        if (output[i] != expected[i]) {
+            char info[100];
+            std::snprintf(info, sizeof(info), "Wrong answer at line %zu.", i + 1);
+            quit(info, 0);
+        }
+    }
+    if (output.size() < expected.size()) {
+        quit("The answer is longer than your output.", 0);
+    }
+    if (output.size() > expected.size()) {
+        quit("Your output is longer than the answer.", 0);
+    }
+    quit("Correct.", 10);
+}
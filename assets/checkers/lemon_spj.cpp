@@ -0,0 +1,100 @@
+// Lemon 特殊评测包装器
+// Lemon 调用方式：spj <输入文件> <选手输出> <标准答案> <满分> <得分文件> <信息文件>
+// 实际的 testlib 检查器为同目录下的 chk_testlib，以 -appes 模式运行并解析其 XML 结果
+#include <cstdio>
+#include <cstdlib>
+#include <fstream>
+#include <sstream>
+#include <string>
+
+#ifdef _WIN32
+static const char* CHECKER_NAME = "chk_testlib.exe";
+#else
+static const char* CHECKER_NAME = "chk_testlib";
+#endif
+
+static std::string quote(const std::string& s)
+{
+    return "\"" + s + "\"";
+}
+
+// 读取 XML 中某个属性的值，testlib 会在等号两侧加空格
+static std::string attribute(const std::string& xml, const std::string& name)
+{
+    size_t pos = xml.find(" " + name);
+    if (pos == std::string::npos) {
+        return "";
+    }
+    pos = xml.find_first_not_of(' ', pos + name.size() + 1);
+    if (pos == std::string::npos || xml[pos] != '=') {
+        return "";
+    }
+    size_t begin = xml.find('"', pos);
+    if (begin == std::string::npos) {
+        return "";
+    }
+    size_t end = xml.find('"', begin + 1);
+    return xml.substr(begin + 1, end - begin - 1);
+}
+
+// 读取 XML 的文本内容
+static std::string text(const std::string& xml)
+{
+    size_t begin = xml.find('>', xml.find("<result"));
+    size_t end = xml.rfind("</result>");
+    if (begin == std::string::npos || end == std::string::npos || end <= begin) {
+        return "";
+    }
+    return xml.substr(begin + 1, end - begin - 1);
+}
+
+static void finish(const char* score_file, const char* info_file, double score, const std::string& info)
+{
+    std::ofstream(score_file) << static_cast<long long>(score + 0.5) << std::endl;
+    std::ofstream(info_file) << info << std::endl;
+    std::exit(0);
+}
+
+int main(int argc, char* argv[])
+{
+    if (argc < 7) {
+        return 1;
+    }
+
+    std::string self = argv[0];
+    size_t slash = self.find_last_of("/\\");
+    std::string dir = slash == std::string::npos ? "" : self.substr(0, slash + 1);
+    std::string result = std::string(argv[6]) + ".xml";
+    double full_score = std::atof(argv[4]);
+
+    std::string command = quote(dir + CHECKER_NAME) + " " + quote(argv[1]) + " " + quote(argv[2]) + " "
+        + quote(argv[3]) + " " + quote(result) + " -appes";
+#ifdef _WIN32
+    // cmd.exe 会去掉整条命令外层的引号
+    command = "\"" + command + "\"";
+#endif
+    std::system(command.c_str());
+
+    std::ifstream file(result);
+    std::stringstream buffer;
+    buffer << file.rdbuf();
+    std::string xml = buffer.str();
+    std::remove(result.c_str());
+
+    std::string outcome = attribute(xml, "outcome");
+    std::string message = text(xml);
+
+    if (outcome == "accepted") {
+        finish(argv[5], argv[6], full_score, message);
+    }
+    if (outcome == "partially-correct" || outcome == "points") {
+        std::string value = attribute(xml, outcome == "points" ? "points" : "pctype");
+        double ratio = std::atof(value.c_str()) / 100.0;
+        ratio = ratio < 0 ? 0 : (ratio > 1 ? 1 : ratio);
+        finish(argv[5], argv[6], full_score * ratio, message);
+    }
+    if (outcome.empty()) {
+        message = "Checker failed to produce a result.";
+    }
+    finish(argv[5], argv[6], 0, message);
+}
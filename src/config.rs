@@ -47,7 +47,7 @@ fn is_contest_config(path: &Path) -> Result<bool> {
             }
         } else {
             error!(
-                "配置文件版本过低，可能是 tuack 的配置文件。请迁移到 tuack-ng 配置文件格式再使用。"
+                "配置文件版本过低，可能是 tuack 的配置文件。请使用 `tuack-ng migrate` 迁移到 tuack-ng 配置文件格式再使用。"
             );
             bail!("配置文件版本过低");
         }
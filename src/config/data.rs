@@ -12,4 +12,30 @@ pub struct DataJson {
     pub file_io: bool,
     pub support_languages: Vec<SupportLanguage>,
     pub problems: Vec<Problem>,
+    /// 题面的语言，未指定语言时省略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 文档中包含的部分
+    pub parts: DocumentParts,
+}
+
+/// 文档中包含的部分，模板据此决定是否输出封面、注意事项与题目
+///
+/// 只有清单中声明了 `"parts": true` 的模板会收到不完整的部分，其余模板总是收到完整的文档。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentParts {
+    pub cover: bool,
+    pub precaution: bool,
+    pub problems: bool,
+}
+
+impl DocumentParts {
+    /// 包含所有部分的完整文档
+    pub fn all() -> Self {
+        DocumentParts {
+            cover: true,
+            precaution: true,
+            problems: true,
+        }
+    }
 }
@@ -34,6 +34,8 @@ pub struct DateInfo {
 pub enum TargetType {
     Typst,
     Markdown,
+    Html,
+    Latex,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -45,6 +47,15 @@ pub struct TemplateManifest {
     #[serde(default = "default_file_io")]
     pub file_io: bool,
     pub target: TargetType,
+    /// 继承的模板名称，比赛目录下 templates 中的模板可以只覆盖部分文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// 默认渲染的语言，`ren` 未指定语言时使用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// 模板是否按 data.json 中的 `parts` 只输出部分内容，`ren --layout` 的 problem、cover 与 precaution 需要
+    #[serde(default)]
+    pub parts: bool,
 }
 
 fn default_use_pretest() -> bool {
@@ -254,7 +254,7 @@ impl DataItem {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
 pub enum ScorePolicy {
     /// 求和（默认）
@@ -275,7 +275,9 @@ pub fn load_problem_config(problemconfig_path: &Path) -> Result<ProblemConfig> {
     if let Some(version) = problem_json_value.get("version").and_then(|v| v.as_u64())
         && version < 3
     {
-        error!("配置文件版本过低，可能是 tuack 的配置文件。请迁移到 tuack-ng 配置文件格式再使用。");
+        error!(
+            "配置文件版本过低，可能是 tuack 的配置文件。请使用 `tuack-ng migrate` 迁移到 tuack-ng 配置文件格式再使用。"
+        );
         bail!("配置文件版本过低");
     }
 
@@ -13,6 +13,8 @@ use std::process::{Command, Stdio};
 use std::sync::Arc;
 use std::time::Duration;
 
+mod verify;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum Target {
     /// 正式测试数据
@@ -44,16 +46,24 @@ pub enum DmkCommand {
 #[command(version, about = "数据生成工具")]
 pub struct DmkArgs {
     /// 目标类型
-    #[arg(value_enum)]
-    pub target: Target,
+    #[arg(value_enum, required_unless_present = "verify")]
+    pub target: Option<Target>,
 
     /// 操作
-    #[arg(value_enum)]
-    pub action: DmkCommand,
+    #[arg(value_enum, required_unless_present = "verify")]
+    pub action: Option<DmkCommand>,
 
     /// 操作对象，使用 `,` 和 `-` 分割 (如 1,2-3,4-10)
     #[arg(default_value = "all")]
     object: String,
+
+    /// 检查生成器与标程的输出是否确定（不修改数据）
+    #[arg(long)]
+    pub verify: bool,
+
+    /// 同时使用 sanitizer 编译标程并在所有数据上运行
+    #[arg(long, requires = "verify")]
+    pub sanitize: bool,
 }
 
 /// 从字符串解析测试点ID集合
@@ -130,6 +140,10 @@ pub fn main(args: DmkArgs) -> Result<()> {
             bail!("本命令只能在题目目录下执行");
         };
 
+    if args.verify {
+        return verify::verify_data(&args, current_problem, current_day);
+    }
+
     gen_data(&args, current_problem, current_day)
 }
 
@@ -139,10 +153,9 @@ fn gen_data(
     current_day: &crate::config::ContestDayConfig,
 ) -> Result<()> {
     info!("开始生成数据: {}", current_problem.name);
-    let target_dir = match args.target {
-        Target::Data => current_problem.path.join("data"),
-        Target::Sample => current_problem.path.join("sample"),
-    };
+    let target = args.target.context("缺少目标类型")?;
+    let action = args.action.clone().context("缺少操作")?;
+    let target_dir = target_dir(target, current_problem);
     if !target_dir.exists() {
         std::fs::create_dir_all(&target_dir)?;
         info!("创建目标目录: {}", target_dir.display());
@@ -152,35 +165,27 @@ fn gen_data(
     info!("找到生成器: {}", generator_path.display());
     info!("找到标程: {}", std_path.display());
 
+    let std_tmp_dir = std_path.parent().unwrap().join("tmp");
+
     // 并行编译生成器和标程
     let (result1, result2) = rayon::join(
         || compile_generator(&generator_path),
-        || compile_std(&std_path, current_problem, current_day),
+        || {
+            compile_std(
+                &std_path,
+                current_problem,
+                &current_day.compile,
+                &std_tmp_dir,
+            )
+        },
     );
     result1?;
     result2?;
 
-    let data_items: Vec<Arc<ExpandedDataItem>> = match args.target {
-        Target::Data => current_problem.data.to_vec(),
-        Target::Sample => current_problem
-            .samples
-            .iter()
-            .map(|item| {
-                Arc::new(ExpandedDataItem {
-                    id: item.id,
-                    score: 0,
-                    subtask: 0,
-                    input: item.input.get().unwrap().clone(),
-                    output: item.output.get().unwrap().clone(),
-                    args: item.args.clone(),
-                    manual: item.manual.unwrap_or(false),
-                })
-            })
-            .collect(),
-    };
-
-    let data_items: Vec<Arc<ExpandedDataItem>> =
-        data_items.into_iter().filter(|item| !item.manual).collect();
+    let data_items: Vec<Arc<ExpandedDataItem>> = collect_data_items(target, current_problem)
+        .into_iter()
+        .filter(|item| !item.manual)
+        .collect();
 
     let all_ids: Vec<u32> = data_items.iter().map(|data| data.id).collect();
     let target_ids = parse_test_object(&args.object, &all_ids)?;
@@ -191,7 +196,7 @@ fn gen_data(
 
     let seeds = get_or_generate_seed(
         &target_dir,
-        matches!(args.action, DmkCommand::Reset),
+        matches!(action, DmkCommand::Reset),
         &data_items_to_gen,
     )?;
 
@@ -219,7 +224,7 @@ fn gen_data(
         let input_path = target_dir.join(&input_file);
         let output_path = target_dir.join(&output_file);
 
-        if !matches!(args.action, DmkCommand::Gen) || !input_path.exists() {
+        if !matches!(action, DmkCommand::Gen) || !input_path.exists() {
             let mut args_map = current_problem.args.clone();
             args_map.extend(data_item.args.clone());
             generate_input(
@@ -231,9 +236,10 @@ fn gen_data(
             )?;
         }
 
-        if !matches!(args.action, DmkCommand::Gen) || !output_path.exists() {
+        if !matches!(action, DmkCommand::Gen) || !output_path.exists() {
             generate_output(
                 &std_path,
+                &std_tmp_dir,
                 &input_path,
                 &output_path,
                 &current_problem.name,
@@ -245,11 +251,44 @@ fn gen_data(
     }
 
     pb.finish_with_message("数据生成完成！");
-    let _ = std::fs::remove_dir_all(std_path.parent().unwrap().join("tmp"));
+    let _ = std::fs::remove_dir_all(&std_tmp_dir);
     save_seed(&target_dir, seeds)?;
     Ok(())
 }
 
+/// 获取目标类型对应的数据目录
+fn target_dir(target: Target, problem: &crate::config::ProblemConfig) -> PathBuf {
+    match target {
+        Target::Data => problem.path.join("data"),
+        Target::Sample => problem.path.join("sample"),
+    }
+}
+
+/// 获取目标类型对应的全部数据点
+fn collect_data_items(
+    target: Target,
+    problem: &crate::config::ProblemConfig,
+) -> Vec<Arc<ExpandedDataItem>> {
+    match target {
+        Target::Data => problem.data.to_vec(),
+        Target::Sample => problem
+            .samples
+            .iter()
+            .map(|item| {
+                Arc::new(ExpandedDataItem {
+                    id: item.id,
+                    score: 0,
+                    subtask: 0,
+                    input: item.input.get().unwrap().clone(),
+                    output: item.output.get().unwrap().clone(),
+                    args: item.args.clone(),
+                    manual: item.manual.unwrap_or(false),
+                })
+            })
+            .collect(),
+    }
+}
+
 /// 查找数据生成器
 fn find_generator(problem_path: &std::path::Path) -> Result<std::path::PathBuf> {
     let path = problem_path.join("gen").join("gen.cpp");
@@ -262,7 +301,7 @@ fn find_generator(problem_path: &std::path::Path) -> Result<std::path::PathBuf>
 }
 
 /// 查找标程
-fn find_std(problem: &crate::config::ProblemConfig) -> Result<std::path::PathBuf> {
+pub(crate) fn find_std(problem: &crate::config::ProblemConfig) -> Result<std::path::PathBuf> {
     for (name, case) in &problem.tests {
         if let crate::config::ExpectedScore::Single(str) = &case.expected
             && str.replace(' ', "") == "==100"
@@ -316,22 +355,22 @@ fn compile_generator(generator_path: &std::path::Path) -> Result<()> {
 }
 
 /// 编译标程
-fn compile_std(
+pub(crate) fn compile_std(
     std_path: &std::path::Path,
     problem: &crate::config::ProblemConfig,
-    day: &crate::config::ContestDayConfig,
+    compile_args: &HashMap<String, String>,
+    tmp_dir: &std::path::Path,
 ) -> Result<()> {
     info!("编译标程: {}", std_path.display());
 
-    let tmp_dir = std_path.parent().unwrap().join("tmp");
-    create_or_clear_dir(&tmp_dir)?;
+    create_or_clear_dir(tmp_dir)?;
 
     let src_path = tmp_dir.join(std_path.file_name().unwrap());
     std::fs::copy(std_path, &src_path)?;
 
     let program_name = problem.name.clone();
 
-    let compile_cmd = build_compile_cmd(&src_path, &tmp_dir, &program_name, &day.compile)?;
+    let compile_cmd = build_compile_cmd(&src_path, tmp_dir, &program_name, compile_args)?;
 
     let compile_pb = get_context().multiprogress.add(ProgressBar::new_spinner());
     compile_pb.enable_steady_tick(Duration::from_millis(100));
@@ -339,7 +378,7 @@ fn compile_std(
 
     if let Some(mut cmd) = compile_cmd {
         let status = cmd
-            .current_dir(&tmp_dir)
+            .current_dir(tmp_dir)
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
             .status()?;
@@ -458,16 +497,47 @@ fn generate_input(
 /// 使用标程生成输出文件
 fn generate_output(
     std_path: &std::path::Path,
+    work_dir: &std::path::Path,
     input_path: &std::path::Path,
     output_path: &std::path::Path,
     problem_name: &str,
     file_io: bool,
 ) -> Result<()> {
-    let tmp_dir = std_path.parent().unwrap().join("tmp");
+    let (output, work_output_path) =
+        run_std(std_path, work_dir, input_path, problem_name, file_io, &[])?;
 
-    // 准备工作目录
-    let work_dir = &tmp_dir;
+    if !output.status.success() {
+        error!("标程运行失败，退出码: {}", output.status);
+        if !output.stderr.is_empty() {
+            error!("错误输出: {}", String::from_utf8_lossy(&output.stderr));
+        }
+        bail!("标程运行失败");
+    }
+
+    // 检查输出文件是否生成
+    if !work_output_path.exists() {
+        error!("标程未生成输出文件: {}", work_output_path.display());
+        bail!("标程未生成输出文件");
+    }
+
+    // 复制输出文件到目标位置
+    std::fs::copy(&work_output_path, output_path)?;
+
+    debug!("成功生成输出文件: {}", output_path.display());
+
+    info!("标程成功生成输出");
+    Ok(())
+}
 
+/// 在工作目录中运行已编译的标程，返回进程输出与标程输出文件路径
+fn run_std(
+    std_path: &std::path::Path,
+    work_dir: &std::path::Path,
+    input_path: &std::path::Path,
+    problem_name: &str,
+    file_io: bool,
+    envs: &[(&str, &str)],
+) -> Result<(std::process::Output, PathBuf)> {
     // 复制输入文件到工作目录
     let work_input_path = if file_io {
         work_dir.join(format!("{}.in", problem_name))
@@ -485,6 +555,11 @@ fn generate_output(
         work_dir.join(format!("{}.stdout", problem_name))
     };
 
+    // 清理上一次运行留下的输出，避免误用旧结果
+    if work_output_path.exists() {
+        fs::remove_file(&work_output_path)?;
+    }
+
     let mut cmd = if let Some(cmd) = build_run_cmd(std_path, work_dir, problem_name)? {
         cmd
     } else {
@@ -500,6 +575,8 @@ fn generate_output(
         std::process::Command::new(executable_path)
     };
 
+    cmd.envs(envs.iter().copied());
+
     // 设置IO重定向
     let child = if file_io {
         cmd.current_dir(work_dir)
@@ -520,25 +597,5 @@ fn generate_output(
     debug!("运行标程命令");
     let output = child.output()?;
 
-    if !output.status.success() {
-        error!("标程运行失败，退出码: {}", output.status);
-        if !output.stderr.is_empty() {
-            error!("错误输出: {}", String::from_utf8_lossy(&output.stderr));
-        }
-        bail!("标程运行失败");
-    }
-
-    // 检查输出文件是否生成
-    if !work_output_path.exists() {
-        error!("标程未生成输出文件: {}", work_output_path.display());
-        bail!("标程未生成输出文件");
-    }
-
-    // 复制输出文件到目标位置
-    std::fs::copy(&work_output_path, output_path)?;
-
-    debug!("成功生成输出文件: {}", output_path.display());
-
-    info!("标程成功生成输出");
-    Ok(())
+    Ok((output, work_output_path))
 }
@@ -0,0 +1,245 @@
+use super::{
+    DmkArgs, Target, collect_data_items, compile_generator, compile_std, create_or_clear_dir,
+    find_generator, find_std, generate_input, generate_output, get_or_generate_seed, run_std,
+    target_dir,
+};
+use crate::config::ExpandedDataItem;
+use crate::prelude::*;
+use crate::utils::compile::sanitizer_profile;
+use indicatif::ProgressBar;
+
+/// sanitizer 报告中会出现的关键字
+const SANITIZER_MARKERS: [&str; 2] = ["runtime error:", "Sanitizer"];
+
+/// 检查生成器与标程的确定性，并可选地使用 sanitizer 检查标程
+pub fn verify_data(args: &DmkArgs, problem: &ProblemConfig, day: &ContestDayConfig) -> Result<()> {
+    let target = args.target.unwrap_or(Target::Data);
+    info!("开始验证 {}: {}", target, problem.name);
+
+    let target_dir = target_dir(target, problem);
+    let std_path = find_std(problem)?;
+    let generator_path = match find_generator(&problem.path) {
+        Ok(path) => Some(path),
+        Err(_) => {
+            warn!("未找到数据生成器，仅检查标程");
+            None
+        }
+    };
+
+    let verify_dir = problem.path.join("tmp").join("verify");
+    create_or_clear_dir(&verify_dir)?;
+    let std_tmp_dir = verify_dir.join("std");
+
+    // 并行编译生成器和标程
+    let (result1, result2) = rayon::join(
+        || generator_path.as_deref().map(compile_generator).transpose(),
+        || compile_std(&std_path, problem, &day.compile, &std_tmp_dir),
+    );
+    result1?;
+    result2?;
+
+    let data_items = collect_data_items(target, problem);
+    // 种子只读取不保存，验证过程不修改数据目录
+    let seeds = get_or_generate_seed(&target_dir, false, &data_items)?;
+    let file_io = problem.file_io.unwrap_or(true);
+
+    let mut issues: Vec<String> = Vec::new();
+
+    let pb = get_context()
+        .multiprogress
+        .add(ProgressBar::new(data_items.len() as u64));
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("  [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+
+    for item in &data_items {
+        pb.set_message(format!("验证数据点 #{}", item.id));
+
+        let input_path = match generator_path {
+            Some(ref generator_path) if !item.manual => {
+                let mut args_map = problem.args.clone();
+                args_map.extend(item.args.clone());
+
+                let first = verify_dir.join(format!("{}.gen1.in", item.id));
+                let second = verify_dir.join(format!("{}.gen2.in", item.id));
+                generate_input(generator_path, &first, &seeds, item.id, &args_map)?;
+                generate_input(generator_path, &second, &seeds, item.id, &args_map)?;
+
+                if fs::read(&first)? != fs::read(&second)? {
+                    warn!("生成器输出不确定: 测试点 #{}", item.id);
+                    issues.push(format!(
+                        "生成器在测试点 #{} 上使用相同种子得到了不同的输出",
+                        item.id
+                    ));
+                }
+                first
+            }
+            _ => {
+                let path = target_dir.join(&item.input);
+                if !path.exists() {
+                    warn!("输入文件不存在，跳过测试点 #{}", item.id);
+                    pb.inc(1);
+                    continue;
+                }
+                path
+            }
+        };
+
+        let first = verify_dir.join(format!("{}.std1.out", item.id));
+        let second = verify_dir.join(format!("{}.std2.out", item.id));
+        let run_twice = generate_output(
+            &std_path,
+            &std_tmp_dir,
+            &input_path,
+            &first,
+            &problem.name,
+            file_io,
+        )
+        .and_then(|_| {
+            generate_output(
+                &std_path,
+                &std_tmp_dir,
+                &input_path,
+                &second,
+                &problem.name,
+                file_io,
+            )
+        });
+
+        match run_twice {
+            Ok(()) => {
+                if fs::read(&first)? != fs::read(&second)? {
+                    warn!("标程输出不确定: 测试点 #{}", item.id);
+                    issues.push(format!(
+                        "标程在测试点 #{} 上对相同输入得到了不同的输出",
+                        item.id
+                    ));
+                }
+            }
+            Err(e) => issues.push(format!("标程在测试点 #{} 上运行失败: {}", item.id, e)),
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    if args.sanitize {
+        issues.extend(sanitize_std(
+            &std_path,
+            problem,
+            day,
+            &target_dir,
+            &data_items,
+            &verify_dir,
+        )?);
+    }
+
+    if issues.is_empty() {
+        let _ = fs::remove_dir_all(&verify_dir);
+        info!("验证通过：生成器与标程输出均确定");
+        return Ok(());
+    }
+
+    for issue in &issues {
+        error!("{}", issue);
+    }
+    warn!("保留验证目录以供调试: {}", verify_dir.display());
+    bail!("验证未通过，共发现 {} 个问题", issues.len())
+}
+
+/// 使用 sanitizer 编译配置编译标程，并在所有数据上运行，收集其中的报告
+fn sanitize_std(
+    std_path: &Path,
+    problem: &ProblemConfig,
+    day: &ContestDayConfig,
+    target_dir: &Path,
+    data_items: &[Arc<ExpandedDataItem>],
+    verify_dir: &Path,
+) -> Result<Vec<String>> {
+    let ext = std_path
+        .extension()
+        .context("文件无后缀名")?
+        .to_string_lossy();
+    if ext != "cpp" && ext != "c" {
+        warn!("sanitizer 仅支持 C/C++ 标程，跳过检查");
+        return Ok(Vec::new());
+    }
+
+    info!("使用 sanitizer 编译标程");
+    let sanitize_dir = verify_dir.join("sanitize");
+    compile_std(
+        std_path,
+        problem,
+        &sanitizer_profile(&day.compile),
+        &sanitize_dir,
+    )?;
+
+    let pb = get_context()
+        .multiprogress
+        .add(ProgressBar::new(data_items.len() as u64));
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("  [{bar:40.magenta/blue}] {pos}/{len} {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+
+    let mut issues = Vec::new();
+
+    for item in data_items {
+        pb.set_message(format!("sanitizer 检查测试点 #{}", item.id));
+
+        let input_path = target_dir.join(&item.input);
+        if !input_path.exists() {
+            warn!("输入文件不存在，跳过测试点 #{}", item.id);
+            pb.inc(1);
+            continue;
+        }
+
+        // 内存泄漏不属于未定义行为，不予报告
+        let (output, _) = run_std(
+            std_path,
+            &sanitize_dir,
+            &input_path,
+            &problem.name,
+            problem.file_io.unwrap_or(true),
+            &[
+                ("ASAN_OPTIONS", "detect_leaks=0"),
+                ("UBSAN_OPTIONS", "print_stacktrace=1"),
+            ],
+        )?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if SANITIZER_MARKERS
+            .iter()
+            .any(|marker| stderr.contains(marker))
+        {
+            let report: Vec<&str> = stderr
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .take(10)
+                .collect();
+            warn!("标程在测试点 #{} 上触发 sanitizer 报告", item.id);
+            issues.push(format!(
+                "标程在测试点 #{} 上触发 sanitizer 报告:\n{}",
+                item.id,
+                report.join("\n")
+            ));
+        } else if !output.status.success() {
+            issues.push(format!(
+                "sanitizer 版本标程在测试点 #{} 上运行失败，退出码: {}",
+                item.id, output.status
+            ));
+        }
+
+        pb.inc(1);
+    }
+
+    pb.finish_and_clear();
+
+    Ok(issues)
+}
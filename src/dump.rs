@@ -1,12 +1,36 @@
+use crate::config::{TargetType, TemplateManifest};
 use crate::prelude::*;
+use crate::ren::template::render_template;
+use crate::ren::utils::parse_statement;
+use crate::ren::{RenderOptions, localized_file};
 use clap::Args;
 use clap::ValueEnum;
+use markdown_ppp::ast::Document;
+use markdown_ppp::printer::config::Config;
+use markdown_ppp::printer::render_markdown;
+use std::io::Write;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
 
+mod arbiter;
+mod down;
+mod hydro;
+mod kattis;
 mod lemon;
+mod loj;
+mod polygon;
+mod uoj;
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum Target {
     Lemon,
+    Arbiter,
+    Uoj,
+    Loj,
+    Hydro,
+    Kattis,
+    Polygon,
+    Down,
 }
 
 #[derive(Args, Debug)]
@@ -15,16 +39,273 @@ pub struct DumpArgs {
     /// 渲染目标模板
     #[arg(required = true)]
     pub target: Target,
+
+    /// 将数据的换行符转换为 CRLF（仅 Lemon）
+    #[arg(long)]
+    pub crlf: bool,
+
+    /// 将导出结果打包为一个 zip 文件
+    #[arg(long)]
+    pub zip: bool,
+}
+
+/// 将指定语言的题面展开模板并解析，供在线评测系统使用
+fn statement_ast(
+    prob: &ProblemConfig,
+    day: &ContestDayConfig,
+    language: Option<&str>,
+) -> Result<Document> {
+    let contest = &get_context()
+        .config
+        .as_ref()
+        .context("没有有效的配置文件")?
+        .0;
+    let statement_path = localized_file(&prob.path, "statement", language);
+    if !statement_path.exists() {
+        bail!("未找到题面文件: {}", statement_path.display());
+    }
+
+    // 在线评测系统默认使用标准输入输出
+    let manifest = TemplateManifest {
+        use_pretest: false,
+        noi_style: false,
+        file_io: false,
+        target: TargetType::Markdown,
+        extends: None,
+        languages: Vec::new(),
+        parts: false,
+    };
+    let options = RenderOptions {
+        language: language.map(str::to_string),
+        ..Default::default()
+    };
+    let content = render_template(
+        &fs::read_to_string(&statement_path)?,
+        prob,
+        day,
+        contest,
+        prob.path.clone(),
+        manifest,
+        &options,
+    )
+    .with_context(|| format!("展开题面模板 {} 失败", statement_path.display()))?;
+
+    let ast = parse_statement(&content)
+        .with_context(|| format!("解析题面文件 {} 失败", statement_path.display()))?;
+
+    if prob.path.join("img").exists() {
+        warn!(
+            "题目 {} 的题面包含图片，请手动上传并修改图片链接",
+            prob.name
+        );
+    }
+
+    Ok(ast)
+}
+
+/// 将指定语言的题面展开模板后渲染为 Markdown
+fn render_statement(
+    prob: &ProblemConfig,
+    day: &ContestDayConfig,
+    language: Option<&str>,
+) -> Result<String> {
+    Ok(render_markdown(
+        &statement_ast(prob, day, language)?,
+        Config::default().with_width(10000000),
+    ))
+}
+
+/// 向 zip 包中写入一个文件
+fn zip_write(zip: &mut ZipWriter<fs::File>, name: &str, content: &[u8]) -> Result<()> {
+    zip.start_file(name, SimpleFileOptions::default())?;
+    zip.write_all(content)?;
+    Ok(())
+}
+
+/// 将目录递归打包为 zip 文件
+fn zip_dir(dir: &Path, zip_path: &Path) -> Result<()> {
+    fn add(zip: &mut ZipWriter<fs::File>, root: &Path, dir: &Path) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                add(zip, root, &path)?;
+            } else {
+                let name = path
+                    .strip_prefix(root)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                zip_write(zip, &name, &fs::read(&path)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    let mut zip = ZipWriter::new(fs::File::create(zip_path)?);
+    add(&mut zip, dir, dir)?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// 收集题目的附加文件：样例、down 目录以及提交答案题的输入数据
+fn attachments(prob: &ProblemConfig) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for sample in &prob.samples {
+        if let Some(input) = sample.input.get() {
+            files.push((
+                format!("{}{}.in", prob.name, sample.id),
+                prob.path.join("sample").join(input),
+            ));
+        }
+        if let Some(output) = sample.output.get() {
+            files.push((
+                format!("{}{}.ans", prob.name, sample.id),
+                prob.path.join("sample").join(output),
+            ));
+        }
+    }
+
+    if matches!(prob.problem_type, ProblemType::Output) {
+        for case in &prob.data {
+            files.push((
+                format!("{}{}.in", prob.name, case.id),
+                prob.path.join("data").join(&case.input),
+            ));
+        }
+    }
+
+    let down_dir = prob.path.join("down");
+    if down_dir.is_dir() {
+        for entry in fs::read_dir(&down_dir)? {
+            let entry = entry?;
+            if entry.path().is_file() {
+                files.push((
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                ));
+            }
+        }
+    }
+
+    Ok(files)
 }
 
-pub fn dump_main(day: &ContestDayConfig, target: Target) -> Result<()> {
-    let dump_dir = day.path.join("dump");
-    if !dump_dir.exists() {
-        fs::create_dir(dump_dir)?;
+/// 导出失败的题目
+pub struct Failure {
+    day: String,
+    problem: String,
+    error: anyhow::Error,
+}
+
+/// 导出一道题目，失败时记录到 `failures` 中并继续导出其他题目
+fn try_problem<T>(
+    failures: &mut Vec<Failure>,
+    day: &ContestDayConfig,
+    prob: &ProblemConfig,
+    f: impl FnOnce() -> Result<T>,
+) -> Option<T> {
+    match f() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("导出题目 {} 失败: {:#}", prob.name, e);
+            failures.push(Failure {
+                day: day.name.clone(),
+                problem: prob.name.clone(),
+                error: e,
+            });
+            None
+        }
     }
+}
 
-    match target {
-        Target::Lemon => lemon::main(day),
+#[derive(Debug, Serialize)]
+struct Index {
+    contest: String,
+    title: String,
+    target: String,
+    days: Vec<IndexDay>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexDay {
+    name: String,
+    title: String,
+    /// 该竞赛日的导出结果相对于导出目录的路径
+    path: String,
+    problems: Vec<IndexProblem>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexProblem {
+    name: String,
+    title: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// 写入导出目录的索引文件 index.json
+fn write_index(
+    contest: &ContestConfig,
+    target: &str,
+    days: &[(&ContestDayConfig, String)],
+    failures: &[Failure],
+    output_dir: &Path,
+) -> Result<()> {
+    let days = days
+        .iter()
+        .map(|(day, path)| IndexDay {
+            name: day.name.clone(),
+            title: day.title.clone(),
+            path: path.clone(),
+            problems: day
+                .subconfig
+                .values()
+                .map(|prob| {
+                    let failure = failures
+                        .iter()
+                        .find(|f| f.day == day.name && f.problem == prob.name);
+                    IndexProblem {
+                        name: prob.name.clone(),
+                        title: prob.title.clone(),
+                        status: if failure.is_some() { "failed" } else { "ok" },
+                        error: failure.map(|f| format!("{:#}", f.error)),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let index = Index {
+        contest: contest.name.clone(),
+        title: contest.title.clone(),
+        target: target.to_string(),
+        days,
+    };
+    fs::write(
+        output_dir.join("index.json"),
+        serde_json::to_string_pretty(&index)?,
+    )?;
+    Ok(())
+}
+
+/// 将一个竞赛日导出到 `output_dir`
+fn dump_day(
+    day: &ContestDayConfig,
+    output_dir: &Path,
+    args: &DumpArgs,
+    failures: &mut Vec<Failure>,
+) -> Result<()> {
+    match args.target {
+        Target::Lemon => lemon::main(day, output_dir, args.crlf, failures),
+        Target::Uoj => uoj::main(day, output_dir, failures),
+        Target::Loj => loj::main(day, output_dir, failures),
+        Target::Hydro => hydro::main(day, output_dir, failures),
+        Target::Kattis => kattis::main(day, output_dir, failures),
+        Target::Polygon => polygon::main(day, output_dir, failures),
+        Target::Down => down::main(day, output_dir, failures),
+        Target::Arbiter => arbiter::main(&day.name, &[day], output_dir, failures),
     }
 }
 
@@ -33,21 +314,74 @@ pub fn main(args: DumpArgs) -> Result<()> {
         bail!("没有有效的配置文件");
     }
     let config = get_context().config.clone().unwrap();
-    match config.1 {
+    let contest = &config.0;
+    let (days, dump_dir): (Vec<&ContestDayConfig>, PathBuf) = match &config.1 {
         CurrentLocation::None => bail!("此命令必须在工程下执行"),
         CurrentLocation::Problem(_, _) => bail!("此命令不能在题目下执行"),
         CurrentLocation::Day(day) => {
-            dump_main(config.0.subconfig.get(&day).unwrap(), args.target)?;
+            let day = contest.subconfig.get(day).unwrap();
+            (vec![day], day.path.join("dump"))
+        }
+        CurrentLocation::Root => (
+            contest.subconfig.values().collect(),
+            contest.path.join("dump"),
+        ),
+    };
+    let is_root = matches!(config.1, CurrentLocation::Root);
+
+    let target = args
+        .target
+        .to_possible_value()
+        .unwrap()
+        .get_name()
+        .to_string();
+    let output_dir = dump_dir.join(&target);
+    if output_dir.exists() {
+        fs::remove_dir_all(&output_dir)?;
+    }
+    fs::create_dir_all(&output_dir)?;
+
+    let mut failures = Vec::new();
+    let mut day_dirs = Vec::new();
+    match args.target {
+        // Arbiter 以整场比赛为单位导出
+        Target::Arbiter if is_root => {
+            arbiter::main(&contest.name, &days, &output_dir, &mut failures)?;
+            day_dirs.extend(days.iter().map(|day| (*day, "main".to_string())));
         }
-        CurrentLocation::Root => {
-            for (_, day_config) in config.0.subconfig {
-                dump_main(&day_config, args.target)?;
+        _ => {
+            for day in &days {
+                info!("导出竞赛日: {}", day.name);
+                // 在比赛根目录下导出时，每个竞赛日各占一个子目录
+                let (day_dir, path) = if is_root {
+                    (output_dir.join(&day.name), day.name.clone())
+                } else {
+                    (output_dir.clone(), ".".to_string())
+                };
+                fs::create_dir_all(&day_dir)?;
+                dump_day(day, &day_dir, &args, &mut failures)?;
+                day_dirs.push((*day, path));
             }
         }
     }
 
-    match args.target {
-        Target::Lemon => (),
+    write_index(contest, &target, &day_dirs, &failures, &output_dir)?;
+
+    if args.zip {
+        let zip_path = dump_dir.join(format!("{}.zip", target));
+        zip_dir(&output_dir, &zip_path)?;
+        info!("已打包到 {}", zip_path.display());
     }
+
+    if !failures.is_empty() {
+        error!("以下题目导出失败：");
+        for failure in &failures {
+            error!("  {}/{}: {:#}", failure.day, failure.problem, failure.error);
+        }
+        bail!("有 {} 道题目导出失败", failures.len());
+    }
+
+    info!("已导出到 {}", output_dir.display());
+
     Ok(())
 }
@@ -0,0 +1,234 @@
+use std::process::Command;
+
+use super::{Failure, try_problem};
+use crate::prelude::*;
+
+/// 按 Arbiter 的格式写入信息文件，每行为键与值直接拼接
+fn write_info(path: &Path, info: &[(String, String)]) -> Result<()> {
+    let content: String = info
+        .iter()
+        .map(|(key, value)| format!("{}{}\n", key, value))
+        .collect();
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 复制文本文件并将换行符转换为 LF
+fn copy_unix(from: &Path, to: &Path) -> Result<()> {
+    let content = fs::read(from).with_context(|| format!("无法读取 {}", from.display()))?;
+    let mut converted = Vec::with_capacity(content.len());
+    for (i, byte) in content.iter().enumerate() {
+        if *byte == b'\r' && content.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        converted.push(*byte);
+    }
+    fs::write(to, converted)?;
+    Ok(())
+}
+
+/// 编译检查器到 filter 目录
+///
+/// 题目使用 chk 时直接编译 chk.cpp，否则编译内置的 Arbiter 默认检查器。
+fn compile_filter(prob: &ProblemConfig, target: &Path) -> Result<()> {
+    let source = if prob.use_chk.unwrap_or(false) {
+        let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+        if !chk_path.exists() {
+            bail!("chk 文件不存在");
+        }
+        warn!(
+            "题目 {} 使用了 chk，Arbiter 的检查器接口与 testlib 不同，请确认 chk 兼容 Arbiter",
+            prob.name
+        );
+        chk_path
+    } else {
+        get_context()
+            .assets_dirs
+            .iter()
+            .map(|dir| dir.join("checkers").join("arbiter_e.cpp"))
+            .find(|path| path.exists())
+            .context("找不到 Arbiter 默认检查器 arbiter_e.cpp")?
+    };
+
+    info!("编译检查器: {}", target.display());
+    let compile_status = Command::new("g++")
+        .arg("-o")
+        .arg(target)
+        .arg(&source)
+        .arg("-O2")
+        .arg("-std=c++17")
+        .status()?;
+
+    if !compile_status.success() {
+        bail!("检查器编译错误");
+    }
+    Ok(())
+}
+
+/// 导出一道题目的数据、评测插件与 taskX_Y.info
+fn dump_problem(
+    prob: &ProblemConfig,
+    day: &ContestDayConfig,
+    day_num: usize,
+    prob_num: usize,
+    main_dir: &Path,
+) -> Result<()> {
+    if !matches!(prob.problem_type, ProblemType::Program) {
+        warn!(
+            "Arbiter 暂时只支持非交互式程序题，题目 {} 可能无法正常评测",
+            prob.name
+        );
+    }
+
+    let mut prob_info = vec![
+        ("TITLE=".to_string(), String::new()),
+        ("NAME=".to_string(), prob.name.clone()),
+        ("RUN=".to_string(), String::new()),
+        ("INFILESUFFIX=".to_string(), "in".to_string()),
+        ("ANSFILESUFFIX=".to_string(), "ans".to_string()),
+        ("PLUG=".to_string(), format!("{}_e", prob.name)),
+        ("TYPE=".to_string(), "SOURCE".to_string()),
+        ("LIMIT=".to_string(), prob.time_limit.to_string()),
+        (
+            "MEMLIMITS=".to_string(),
+            (prob.memory_limit.as_mib() as u32).to_string(),
+        ),
+        ("SAMPLES=".to_string(), prob.data.len().to_string()),
+    ];
+
+    for (lang, compiler, prefix) in [
+        ("c", "gcc", " -o %o %i "),
+        ("cpp", "g++", " -o %o %i "),
+        ("pas", "fpc", " %i "),
+    ] {
+        prob_info.push((
+            format!("CCL={}@{}", lang, compiler),
+            format!(
+                "{}{}",
+                prefix,
+                day.compile.get(lang).map(String::as_str).unwrap_or("")
+            ),
+        ));
+    }
+
+    // 数据按子任务顺序编号，Arbiter 不支持捆绑测试，子任务的分数均分给其中各测试点
+    let mut idx = 0;
+    for task in prob.subtasks.values() {
+        let scores: Vec<u32> = match task.policy {
+            ScorePolicy::Sum => task.items.iter().map(|case| case.score).collect(),
+            ScorePolicy::Min | ScorePolicy::Max => {
+                let count = task.items.len() as u32;
+                if count > 1 {
+                    warn!(
+                        "Arbiter 不支持捆绑测试，题目 {} 的子任务分数将均分给其中各测试点",
+                        prob.name
+                    );
+                }
+                (0..count)
+                    .map(|i| task.max_score / count + u32::from(i < task.max_score % count))
+                    .collect()
+            }
+        };
+
+        for (case, score) in task.items.iter().zip(scores) {
+            idx += 1;
+            copy_unix(
+                &prob.path.join("data").join(&case.input),
+                &main_dir
+                    .join("data")
+                    .join(format!("{}{}.in", prob.name, idx)),
+            )?;
+            copy_unix(
+                &prob.path.join("data").join(&case.output),
+                &main_dir
+                    .join("data")
+                    .join(format!("{}{}.ans", prob.name, idx)),
+            )?;
+            prob_info.push((format!("MARK={}@", idx), score.to_string()));
+        }
+    }
+
+    compile_filter(
+        prob,
+        &main_dir.join("filter").join(format!("{}_e", prob.name)),
+    )?;
+
+    write_info(
+        &main_dir.join(format!("task{}_{}.info", day_num, prob_num)),
+        &prob_info,
+    )?;
+
+    Ok(())
+}
+
+/// 导出一个竞赛日，`day_num` 从 1 开始
+fn dump_day(
+    day: &ContestDayConfig,
+    day_num: usize,
+    main_dir: &Path,
+    failures: &mut Vec<Failure>,
+) -> Result<()> {
+    fs::create_dir_all(main_dir.join("players").join(format!("day{}", day_num)))?;
+    fs::create_dir_all(main_dir.join("result").join(format!("day{}", day_num)))?;
+
+    let day_info = vec![
+        ("NAME=".to_string(), format!("第{}场--机试", day_num)),
+        ("PLAYERDIR=".to_string(), String::new()),
+        ("CASEDIR=".to_string(), String::new()),
+        ("BASESCORE=".to_string(), "0".to_string()),
+        ("TASKNUM=".to_string(), day.subconfig.len().to_string()),
+    ];
+    write_info(&main_dir.join(format!("day{}.info", day_num)), &day_info)?;
+
+    for (prob_num, prob) in day.subconfig.values().enumerate() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || {
+            dump_problem(prob, day, day_num, prob_num + 1, main_dir)
+        });
+    }
+
+    Ok(())
+}
+
+/// 导出为 Arbiter 的 main 目录，多个竞赛日按顺序编号
+pub fn main(
+    name: &str,
+    days: &[&ContestDayConfig],
+    output_dir: &Path,
+    failures: &mut Vec<Failure>,
+) -> Result<()> {
+    let main_dir = output_dir.join("main");
+    for dir in ["data", "final", "players", "result", "filter", "tmp"] {
+        fs::create_dir_all(main_dir.join(dir))?;
+    }
+
+    for (day_num, day) in days.iter().enumerate() {
+        info!("导出竞赛日: {}", day.name);
+        dump_day(day, day_num + 1, &main_dir, failures)?;
+    }
+
+    // 评测时使用的数据与 data 相同
+    fs::create_dir_all(main_dir.join("evaldata"))?;
+    for entry in fs::read_dir(main_dir.join("data"))? {
+        let entry = entry?;
+        fs::copy(
+            entry.path(),
+            main_dir.join("evaldata").join(entry.file_name()),
+        )?;
+    }
+
+    let setup = vec![
+        ("NAME=".to_string(), name.to_string()),
+        ("DAYNUM=".to_string(), days.len().to_string()),
+        ("ENV=".to_string(), "env.info".to_string()),
+        ("PLAYER=".to_string(), "player.info".to_string()),
+        ("TEAM=".to_string(), "team.info".to_string()),
+        ("MISC=".to_string(), "misc.info".to_string()),
+    ];
+    write_info(&main_dir.join("setup.cfg"), &setup)?;
+    write_info(&main_dir.join("team.info"), &[])?;
+
+    warn!("Arbiter 的选手信息需要在 Arbiter 中手动导入。");
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use super::{Failure, attachments, try_problem, zip_write};
+use crate::prelude::*;
+use zip::ZipWriter;
+
+/// 交互题提供给选手的头文件与 grader，位于 data/interactor 目录下
+fn graders(prob: &ProblemConfig) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let interactor_dir = prob.path.join("data").join("interactor");
+    if !matches!(prob.problem_type, ProblemType::Interactive) || !interactor_dir.is_dir() {
+        return Ok(files);
+    }
+
+    for entry in fs::read_dir(&interactor_dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let is_header = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("h" | "hpp")
+        );
+        let is_grader = path.file_stem().is_some_and(|stem| stem == "grader");
+        if (is_header && name != "testlib.h") || is_grader {
+            files.push((name, path));
+        }
+    }
+
+    Ok(files)
+}
+
+/// 目录下的所有子目录
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// 查找 `ren` 生成的竞赛日题面 PDF，每种语言取最新的一个
+///
+/// 题面位于 `statements/<模板>/` 下，多语言时位于 `statements/<模板>/<语言>/` 下。
+/// 返回的键为语言，未区分语言的题面为 `None`。
+fn find_statements(day: &ContestDayConfig) -> BTreeMap<Option<String>, PathBuf> {
+    let mut statements: BTreeMap<Option<String>, PathBuf> = BTreeMap::new();
+    let Some((contest, _)) = get_context().config.as_ref() else {
+        return statements;
+    };
+    let modified = |path: &Path| path.metadata().and_then(|m| m.modified()).ok();
+
+    for target_dir in [day.path.join("statements"), contest.path.join("statements")]
+        .iter()
+        .flat_map(|dir| subdirs(dir))
+    {
+        let mut candidates = vec![(None, target_dir.join(format!("{}.pdf", day.name)))];
+        for lang_dir in subdirs(&target_dir) {
+            let language = lang_dir.file_name().unwrap().to_string_lossy().to_string();
+            candidates.push((Some(language), lang_dir.join(format!("{}.pdf", day.name))));
+        }
+        for (language, path) in candidates {
+            if !path.is_file() {
+                continue;
+            }
+            let newer = statements
+                .get(&language)
+                .is_none_or(|old| modified(&path) > modified(old));
+            if newer {
+                statements.insert(language, path);
+            }
+        }
+    }
+
+    statements
+}
+
+/// 导出一道题目的选手文件，同时写入 zip 包
+fn dump_problem(
+    prob: &ProblemConfig,
+    output_dir: &Path,
+    zip: &mut ZipWriter<fs::File>,
+) -> Result<()> {
+    // 与题面中文件表格的“目录”一栏一致
+    let prob_dir = output_dir.join(&prob.name);
+    fs::create_dir(&prob_dir)?;
+
+    let mut files = attachments(prob)?;
+    files.extend(graders(prob)?);
+
+    for (name, path) in files {
+        let content = fs::read(&path).with_context(|| format!("无法读取 {}", path.display()))?;
+        fs::write(prob_dir.join(&name), &content)?;
+        zip_write(zip, &format!("{}/{}", prob.name, name), &content)?;
+    }
+
+    Ok(())
+}
+
+/// 导出选手下发文件，每道题目一个目录，并为竞赛日生成一个 zip 包
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    let zip_path = output_dir.join(format!("{}.zip", day.name));
+    let mut zip = ZipWriter::new(fs::File::create(&zip_path)?);
+
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || {
+            dump_problem(prob, output_dir, &mut zip)
+        });
+    }
+
+    let statements = find_statements(day);
+    if statements.is_empty() {
+        warn!(
+            "未找到竞赛日 {} 的题面 PDF，请先在竞赛日或比赛目录下执行 ren",
+            day.name
+        );
+    }
+    for (language, statement) in statements {
+        let name = match language {
+            Some(language) => format!("{}-{}.pdf", day.name, language),
+            None => format!("{}.pdf", day.name),
+        };
+        let content = fs::read(&statement)?;
+        fs::write(output_dir.join(&name), &content)?;
+        zip_write(&mut zip, &name, &content)?;
+        info!("已添加题面: {}", statement.display());
+    }
+
+    zip.finish()?;
+    info!("已打包到 {}", zip_path.display());
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use super::{Failure, attachments, render_statement, try_problem, zip_write};
+use crate::prelude::*;
+use zip::ZipWriter;
+
+#[derive(Debug, Serialize)]
+struct ProblemYaml {
+    pid: String,
+    title: String,
+    tag: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigYaml {
+    #[serde(rename = "type")]
+    problem_type: String,
+    time: String,
+    memory: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checker_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checker: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interactor: Option<String>,
+    subtasks: Vec<Subtask>,
+}
+
+#[derive(Debug, Serialize)]
+struct Subtask {
+    id: u32,
+    score: u32,
+    #[serde(rename = "type")]
+    subtask_type: String,
+    cases: Vec<Case>,
+}
+
+#[derive(Debug, Serialize)]
+struct Case {
+    input: String,
+    output: String,
+}
+
+/// 导出一道题目为 Hydro 的题目包
+fn dump_problem(prob: &ProblemConfig, day: &ContestDayConfig, output_dir: &Path) -> Result<()> {
+    let file = fs::File::create(output_dir.join(format!("{}.zip", prob.name)))?;
+    let mut zip = ZipWriter::new(file);
+    let root = &prob.name;
+
+    let problem_yaml = ProblemYaml {
+        pid: prob.name.clone(),
+        title: prob.title.clone(),
+        tag: Vec::new(),
+    };
+    zip_write(
+        &mut zip,
+        &format!("{}/problem.yaml", root),
+        serde_yaml::to_string(&problem_yaml)?.as_bytes(),
+    )?;
+    zip_write(
+        &mut zip,
+        &format!("{}/problem_zh.md", root),
+        render_statement(prob, day, None)?.as_bytes(),
+    )?;
+
+    // 测试数据按子任务顺序编号
+    let mut subtasks = Vec::new();
+    let mut idx = 0;
+    for task in prob.subtasks.values() {
+        let mut cases = Vec::new();
+        for case in &task.items {
+            idx += 1;
+            let input = format!("{}.in", idx);
+            let output = format!("{}.ans", idx);
+            zip_write(
+                &mut zip,
+                &format!("{}/testdata/{}", root, input),
+                &fs::read(prob.path.join("data").join(&case.input))?,
+            )?;
+            zip_write(
+                &mut zip,
+                &format!("{}/testdata/{}", root, output),
+                &fs::read(prob.path.join("data").join(&case.output))?,
+            )?;
+            cases.push(Case { input, output });
+        }
+        match task.policy {
+            // Hydro 将子任务的分数平均分给各测试点，求和的子任务中每个测试点各为一个子任务
+            ScorePolicy::Sum => {
+                for (item, case) in task.items.iter().zip(cases) {
+                    subtasks.push(Subtask {
+                        id: subtasks.len() as u32 + 1,
+                        score: item.score,
+                        subtask_type: "sum".to_string(),
+                        cases: vec![case],
+                    });
+                }
+            }
+            ScorePolicy::Min | ScorePolicy::Max => subtasks.push(Subtask {
+                id: subtasks.len() as u32 + 1,
+                score: task.max_score,
+                subtask_type: match task.policy {
+                    ScorePolicy::Min => "min",
+                    _ => "max",
+                }
+                .to_string(),
+                cases,
+            }),
+        }
+    }
+
+    let mut config = ConfigYaml {
+        problem_type: match prob.problem_type {
+            ProblemType::Program => "default",
+            ProblemType::Output => "submit_answer",
+            ProblemType::Interactive => "interactive",
+        }
+        .to_string(),
+        time: format!("{}ms", (prob.time_limit * 1000.0) as u32),
+        memory: format!("{}m", prob.memory_limit.as_mib() as u32),
+        filename: prob.file_io.unwrap_or(false).then(|| prob.name.clone()),
+        checker_type: None,
+        checker: None,
+        interactor: None,
+        subtasks,
+    };
+
+    if matches!(prob.problem_type, ProblemType::Interactive) {
+        let interactor = prob
+            .path
+            .join("data")
+            .join("interactor")
+            .join("interactor.cpp");
+        if !interactor.exists() {
+            bail!(
+                "题目 {} 为交互题，但找不到交互器 data/interactor/interactor.cpp",
+                prob.name
+            );
+        }
+        zip_write(
+            &mut zip,
+            &format!("{}/testdata/interactor.cpp", root),
+            &fs::read(interactor)?,
+        )?;
+        config.interactor = Some("interactor.cpp".to_string());
+    }
+
+    if prob.use_chk.unwrap_or(false) {
+        let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+        if !chk_path.exists() {
+            bail!("chk 文件不存在");
+        }
+        zip_write(
+            &mut zip,
+            &format!("{}/testdata/chk.cpp", root),
+            &fs::read(chk_path)?,
+        )?;
+        config.checker_type = Some("testlib".to_string());
+        config.checker = Some("chk.cpp".to_string());
+    }
+
+    zip_write(
+        &mut zip,
+        &format!("{}/testdata/config.yaml", root),
+        serde_yaml::to_string(&config)?.as_bytes(),
+    )?;
+
+    for (name, path) in attachments(prob)? {
+        zip_write(
+            &mut zip,
+            &format!("{}/additional_file/{}", root, name),
+            &fs::read(path)?,
+        )?;
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
+
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || dump_problem(prob, day, output_dir));
+    }
+
+    info!("请在 Hydro 的题库中使用“导入题目”上传生成的 zip 文件");
+
+    Ok(())
+}
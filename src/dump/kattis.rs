@@ -0,0 +1,168 @@
+use super::{Failure, render_statement, try_problem};
+use crate::prelude::*;
+use crate::test::check_test_case;
+use crate::utils::filesystem::copy_dir_recursive;
+
+#[derive(Debug, Serialize)]
+struct ProblemYaml {
+    name: String,
+    validation: String,
+    limits: Limits,
+}
+
+#[derive(Debug, Serialize)]
+struct Limits {
+    time_limit: f64,
+    memory: u32,
+}
+
+/// 复制文件，目标目录不存在时自动创建
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("复制 {} 到 {} 失败", from.display(), to.display()))?;
+    Ok(())
+}
+
+/// 根据期望得分判断提交的类别
+///
+/// 只能得满分的为 accepted，不能得满分的统一归为 wrong_answer。
+fn submission_kind(test: &TestCase) -> Option<&'static str> {
+    let full = check_test_case(test, 100);
+    let partial = (0..100).any(|score| check_test_case(test, score));
+    match (full, partial) {
+        (true, false) => Some("accepted"),
+        (false, true) => Some("wrong_answer"),
+        _ => None,
+    }
+}
+
+/// 导出一道题目为 Kattis 题目包
+fn dump_problem(prob: &ProblemConfig, day: &ContestDayConfig, output_dir: &Path) -> Result<()> {
+    if !matches!(prob.problem_type, ProblemType::Program) {
+        bail!("Kattis 题目包仅支持传统题，题目 {} 不是传统题", prob.name);
+    }
+
+    let prob_dir = output_dir.join(&prob.name);
+    fs::create_dir(&prob_dir)?;
+
+    if prob.subtasks.len() > 1 {
+        warn!(
+            "ICPC 赛制只判断是否通过，题目 {} 的子任务与分数将被忽略",
+            prob.name
+        );
+    }
+    if prob.file_io.unwrap_or(false) {
+        warn!(
+            "Kattis 只支持标准输入输出，题目 {} 的文件输入输出将被忽略，题面按标准输入输出生成",
+            prob.name
+        );
+    }
+
+    // 样例
+    for sample in &prob.samples {
+        let (Some(input), Some(output)) = (sample.input.get(), sample.output.get()) else {
+            warn!("题目 {} 的样例 {} 缺少文件，已跳过", prob.name, sample.id);
+            continue;
+        };
+        let sample_dir = prob_dir.join("data").join("sample");
+        copy_file(
+            &prob.path.join("sample").join(input),
+            &sample_dir.join(format!("{}.in", sample.id)),
+        )?;
+        copy_file(
+            &prob.path.join("sample").join(output),
+            &sample_dir.join(format!("{}.ans", sample.id)),
+        )?;
+    }
+
+    // 测试数据
+    let secret_dir = prob_dir.join("data").join("secret");
+    fs::create_dir_all(&secret_dir)?;
+    for case in &prob.data {
+        copy_file(
+            &prob.path.join("data").join(&case.input),
+            &secret_dir.join(format!("{}.in", case.id)),
+        )?;
+        copy_file(
+            &prob.path.join("data").join(&case.output),
+            &secret_dir.join(format!("{}.ans", case.id)),
+        )?;
+    }
+
+    // 输出校验器
+    let use_chk = prob.use_chk.unwrap_or(false);
+    if use_chk {
+        let chk_dir = prob.path.join("data").join("chk");
+        if !chk_dir.join("chk.cpp").exists() {
+            bail!("chk 文件不存在");
+        }
+        copy_dir_recursive(&chk_dir, prob_dir.join("output_validators").join("chk"))?;
+        warn!(
+            "题目 {} 的 chk 已复制到 output_validators，Kattis 的校验器接口与 testlib 不同，请手动适配",
+            prob.name
+        );
+    }
+
+    // 输入校验器
+    let val_dir = prob.path.join("val");
+    if val_dir.is_dir() {
+        copy_dir_recursive(&val_dir, prob_dir.join("input_validators").join("val"))?;
+        warn!(
+            "Kattis 的输入校验器以返回值 42 表示合法，请确认题目 {} 的校验器符合要求",
+            prob.name
+        );
+    }
+
+    // 提交
+    for (name, test) in &prob.tests {
+        let Some(kind) = submission_kind(test) else {
+            warn!("无法根据期望得分判断 {} 的类别，已跳过", name);
+            continue;
+        };
+        let path = prob.path.join(&test.path);
+        let target_dir = prob_dir.join("submissions").join(kind);
+        fs::create_dir_all(&target_dir)?;
+        if path.is_dir() {
+            copy_dir_recursive(&path, target_dir.join(path.file_name().unwrap()))?;
+        } else {
+            copy_file(&path, &target_dir.join(path.file_name().unwrap()))?;
+        }
+    }
+
+    // 题面
+    let statement_dir = prob_dir.join("problem_statement");
+    fs::create_dir_all(&statement_dir)?;
+    fs::write(
+        statement_dir.join("problem.en.md"),
+        render_statement(prob, day, Some("en"))?,
+    )?;
+
+    let problem_yaml = ProblemYaml {
+        name: prob.title.clone(),
+        validation: if use_chk { "custom" } else { "default" }.to_string(),
+        limits: Limits {
+            time_limit: prob.time_limit,
+            memory: prob.memory_limit.as_mib() as u32,
+        },
+    };
+    fs::write(
+        prob_dir.join("problem.yaml"),
+        serde_yaml::to_string(&problem_yaml)?,
+    )?;
+    // DOMjudge 从该文件读取时间限制
+    fs::write(prob_dir.join(".timelimit"), prob.time_limit.to_string())?;
+
+    Ok(())
+}
+
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || dump_problem(prob, day, output_dir));
+    }
+
+    Ok(())
+}
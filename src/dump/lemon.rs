@@ -1,178 +1,297 @@
 use serde_json::{Map, Value, json};
 use std::process::Command;
 
+use crate::config::ExpandedDataItem;
 use crate::prelude::*;
 
-pub fn main(day: &ContestDayConfig) -> Result<()> {
-    let output_dir = day.path.join("dump/lemon");
+use super::{Failure, try_problem};
 
-    if output_dir.exists() {
-        fs::remove_dir_all(&output_dir)?;
+/// Lemon 中编译配置的名称，使用新安装的 Lemon 中一定存在的默认配置
+const CONFIGURATION_NAME: &str = "default";
+
+/// 复制数据文件，需要时将换行符转换为 CRLF
+fn copy_data(from: &Path, to: &Path, crlf: bool) -> Result<()> {
+    if !crlf {
+        fs::copy(from, to)
+            .with_context(|| format!("复制 {} 到 {} 失败", from.display(), to.display()))?;
+        return Ok(());
     }
-    fs::create_dir(&output_dir)?;
-    fs::create_dir(output_dir.join("data"))?;
+    let content = fs::read(from).with_context(|| format!("无法读取 {}", from.display()))?;
+    let mut converted = Vec::with_capacity(content.len());
+    for (i, byte) in content.iter().enumerate() {
+        if *byte == b'\n' && (i == 0 || content[i - 1] != b'\r') {
+            converted.push(b'\r');
+        }
+        converted.push(*byte);
+    }
+    fs::write(to, converted)?;
+    Ok(())
+}
 
-    let mut prob_jsons: Vec<Value> = Vec::new();
+/// 在资源目录中查找检查器相关文件
+fn find_checker_asset(name: &str) -> Result<PathBuf> {
+    get_context()
+        .assets_dirs
+        .iter()
+        .map(|dir| dir.join("checkers").join(name))
+        .find(|path| path.exists())
+        .with_context(|| format!("找不到 {}", name))
+}
 
-    for (_, prob) in &day.subconfig {
-        fs::create_dir(output_dir.join("data").join(&prob.name))?;
-        let mut cases: Vec<Value> = Vec::new();
-
-        // 拷贝数据
-        for case in &prob.data {
-            fs::copy(
-                prob.path.join("data").join(&case.input),
-                output_dir
-                    .join("data")
-                    .join(&prob.name)
-                    .join(prob.name.clone() + &case.id.to_string() + ".in"),
-            )?;
-            fs::copy(
-                prob.path.join("data").join(&case.output),
-                output_dir
-                    .join("data")
-                    .join(&prob.name)
-                    .join(prob.name.clone() + &case.id.to_string() + ".ans"),
-            )?;
-        }
+fn compile_cpp(source: &Path, target: &Path) -> Result<()> {
+    let compile_status = Command::new("g++")
+        .arg("-o")
+        .arg(target)
+        .arg(source)
+        .arg("-O2")
+        .arg("-std=c++23")
+        .status()?;
 
-        // 处理配置文件
-        for task in prob.subtasks.values() {
-            match task.policy {
-                ScorePolicy::Sum => {
-                    for case in &task.items {
-                        cases.push(Value::Object(
-                            json!({
-                                "fullScore": case.score,
-                                "timeLimit": (prob.time_limit*1000.0) as u32,
-                                "memoryLimit": prob.memory_limit.as_mib() as u32,
-                                "inputFiles": json!([prob.name.clone() + "/" + &prob.name.clone() + &case.id.to_string() + ".in"]),
-                                "outputFiles": json!([prob.name.clone() + "/" + &prob.name.clone() + &case.id.to_string() + ".ans"]),
-                            })
-                            .as_object()
-                            .unwrap()
-                            .to_owned(),
-                        ));
-                    }
-                }
-                ScorePolicy::Min => {
-                    let mut inputs: Vec<Value> = Vec::new();
-                    let mut outputs: Vec<Value> = Vec::new();
-                    for case in &task.items {
-                        inputs.push(Value::String(
-                            prob.name.clone()
-                                + "/"
-                                + &prob.name.clone()
-                                + &case.id.to_string()
-                                + ".in",
-                        ));
-                        outputs.push(Value::String(
-                            prob.name.clone()
-                                + "/"
-                                + &prob.name.clone()
-                                + &case.id.to_string()
-                                + ".ans",
-                        ));
-                    }
-                    cases.push(Value::Object(
-                        json!({
-                            "fullScore": task.max_score,
-                            "timeLimit": (prob.time_limit*1000.0) as u32,
-                            "memoryLimit": prob.memory_limit.as_mib() as u32,
-                            "inputFiles": inputs,
-                            "outputFiles": outputs
-                        })
-                        .as_object()
-                        .unwrap()
-                        .to_owned(),
+    if !compile_status.success() {
+        bail!("SPJ 编译错误");
+    }
+    Ok(())
+}
+
+/// 嵌入 SPJ
+///
+/// testlib 的检查器接口与 Lemon 不同，因此将 chk 编译为 `chk_testlib`，
+/// 再由包装器 `chk` 转换调用方式。源文件一并复制，以便在其他平台上重新编译。
+fn embed_spj(prob: &ProblemConfig, prob_data_dir: &Path) -> Result<()> {
+    info!("尝试编译 SPJ");
+
+    let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+    if !chk_path.exists() {
+        bail!("chk 文件不存在");
+    }
+    let wrapper_path = find_checker_asset("lemon_spj.cpp")?;
+
+    compile_cpp(
+        &chk_path,
+        &prob_data_dir
+            .join("chk_testlib")
+            .with_extension(std::env::consts::EXE_EXTENSION),
+    )?;
+    compile_cpp(
+        &wrapper_path,
+        &prob_data_dir
+            .join("chk")
+            .with_extension(std::env::consts::EXE_EXTENSION),
+    )?;
+
+    let source_dir = prob_data_dir.join("chk_src");
+    fs::create_dir(&source_dir)?;
+    fs::copy(&chk_path, source_dir.join("chk.cpp"))?;
+    fs::copy(&wrapper_path, source_dir.join("lemon_spj.cpp"))?;
+    let testlib = prob.path.join("data").join("chk").join("testlib.h");
+    let testlib = if testlib.exists() {
+        Some(testlib)
+    } else {
+        find_checker_asset("testlib.h").ok()
+    };
+    if let Some(testlib) = testlib {
+        fs::copy(testlib, source_dir.join("testlib.h"))?;
+    }
+
+    Ok(())
+}
+
+/// 导出一道题目，返回 Lemon 的题目 JSON
+///
+/// 无法完全表示的设置会记录到 `downgrades` 中。
+fn dump_problem(
+    prob: &ProblemConfig,
+    day: &ContestDayConfig,
+    output_dir: &Path,
+    crlf: bool,
+    downgrades: &mut Vec<String>,
+) -> Result<Value> {
+    // 交互题已在调用前跳过
+    let task_type = if matches!(prob.problem_type, ProblemType::Output) {
+        1
+    } else {
+        0
+    };
+
+    let prob_data_dir = output_dir.join("data").join(&prob.name);
+    fs::create_dir(&prob_data_dir)?;
+
+    // 拷贝数据
+    for case in &prob.data {
+        copy_data(
+            &prob.path.join("data").join(&case.input),
+            &prob_data_dir.join(prob.name.clone() + &case.id.to_string() + ".in"),
+            crlf,
+        )?;
+        copy_data(
+            &prob.path.join("data").join(&case.output),
+            &prob_data_dir.join(prob.name.clone() + &case.id.to_string() + ".ans"),
+            crlf,
+        )?;
+    }
+
+    let input_file = |case: &ExpandedDataItem| {
+        Value::String(prob.name.clone() + "/" + &prob.name + &case.id.to_string() + ".in")
+    };
+    let output_file = |case: &ExpandedDataItem| {
+        Value::String(prob.name.clone() + "/" + &prob.name + &case.id.to_string() + ".ans")
+    };
+    let test_case = |score: u32, inputs: Vec<Value>, outputs: Vec<Value>| {
+        json!({
+            "fullScore": score,
+            "timeLimit": (prob.time_limit*1000.0) as u32,
+            "memoryLimit": prob.memory_limit.as_mib() as u32,
+            "inputFiles": inputs,
+            "outputFiles": outputs,
+        })
+    };
+
+    // 处理配置文件
+    let mut cases: Vec<Value> = Vec::new();
+    for (id, task) in &prob.subtasks {
+        let policy = match task.policy {
+            // 只有一个测试点时 max 与 min 等价
+            ScorePolicy::Max if task.items.len() == 1 => ScorePolicy::Min,
+            ScorePolicy::Max => {
+                downgrades.push(format!(
+                    "题目 {} 的子任务 {} 使用 max 评分方法，已按 min 导出",
+                    prob.name, id
+                ));
+                ScorePolicy::Min
+            }
+            policy => policy,
+        };
+        match policy {
+            ScorePolicy::Sum => {
+                for case in &task.items {
+                    cases.push(test_case(
+                        case.score,
+                        vec![input_file(case)],
+                        vec![output_file(case)],
                     ));
                 }
-                ScorePolicy::Max => bail!("lemon 不支持 max 评分方法"),
+            }
+            _ => {
+                cases.push(test_case(
+                    task.max_score,
+                    task.items.iter().map(|case| input_file(case)).collect(),
+                    task.items.iter().map(|case| output_file(case)).collect(),
+                ));
             }
         }
-        // debug!("{cases:#?}");
+    }
 
-        // SPJ
-        if prob.use_chk.unwrap_or(false) {
-            info!("尝试编译 SPJ");
+    // SPJ
+    let use_chk = prob.use_chk.unwrap_or(false);
+    if use_chk {
+        embed_spj(prob, &prob_data_dir)?;
+    }
 
-            let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
-            if !chk_path.exists() {
-                bail!("chk 文件不存在");
-            }
-            let compile_status = Command::new("g++")
-                .arg("-o")
-                .arg(
-                    output_dir
-                        .join("data")
-                        .join(&prob.name)
-                        .join("chk")
-                        .with_extension(std::env::consts::EXE_EXTENSION),
-                )
-                .arg(&chk_path)
-                .arg("-O2")
-                .arg("-std=c++23")
-                .status()?;
-
-            if !compile_status.success() {
-                bail!("SPJ 编译错误");
-            }
-        }
+    // 编译选项
+    let mut compilers: Map<String, Value> = Map::new();
+    for lang in day.compile.keys() {
+        compilers.insert(
+            lemon_compiler(lang)?.to_string(),
+            Value::String(CONFIGURATION_NAME.to_string()),
+        );
+    }
+
+    // 组装这道题的 JSON
+    Ok(json!({
+        "answerFileExtension": "out",
+        "comparisonMode": if use_chk {4} else {1},
+        "specialJudge": if use_chk {
+            PathBuf::from(prob.name.clone())
+                .join("chk")
+                .with_extension(std::env::consts::EXE_EXTENSION)
+                .to_string_lossy()
+                .replace('\\', "/")
+        } else {
+            String::new()
+        },
+        "diffArguments": "--ignore-space-change --text --brief",
+        "inputFileName": prob.name.clone() + ".in",
+        "outputFileName": prob.name.clone() + ".out",
+        "problemTitle": prob.title,
+        "sourceFileName": prob.name,
+        "taskType": task_type,
+        "compilerConfiguration": compilers,
+        "testCases": cases
+    }))
+}
+
+/// 语言对应的 Lemon 编译器名称
+fn lemon_compiler(lang: &str) -> Result<&'static str> {
+    Ok(match lang {
+        "cpp" => "g++",
+        "c" => "gcc",
+        "pas" => "fpc",
+        "py" => "python",
+        "java" => "javac",
+        other => bail!("不支持的语言: {other}"),
+    })
+}
 
-        // 组装这道题的 JSON
-        let mut compilers: Map<String, Value> = Map::new();
-
-        for lang in day.compile.keys() {
-            compilers.insert(
-                match lang.as_str() {
-                    "cpp" => "g++",
-                    "c" => "gcc",
-                    "pas" => "fpc",
-                    "py" => "python",
-                    "java" => "javac",
-                    other => bail!("不支持的语言: {other}"),
+pub fn main(
+    day: &ContestDayConfig,
+    output_dir: &Path,
+    crlf: bool,
+    failures: &mut Vec<Failure>,
+) -> Result<()> {
+    fs::create_dir(output_dir.join("data"))?;
+
+    let mut prob_jsons: Vec<Value> = Vec::new();
+    let mut downgrades: Vec<String> = Vec::new();
+
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        match prob.problem_type {
+            ProblemType::Interactive => {
+                downgrades.push(format!("题目 {} 为交互题，Lemon 不支持，已跳过", prob.name));
+            }
+            _ => {
+                // 导出失败的题目不写入 cdf
+                if let Some(prob_json) = try_problem(failures, day, prob, || {
+                    dump_problem(prob, day, output_dir, crlf, &mut downgrades)
+                }) {
+                    prob_jsons.push(prob_json);
                 }
-                .to_string(),
-                Value::String("default".to_string()),
-            );
+            }
         }
-
-        let prob_json = json!({
-            "answerFileExtension": "out",
-            "comparisonMode": if prob.use_chk.unwrap_or(false) {4} else {1},
-            "specialJudge": PathBuf::from(prob.name.clone())
-                            .join("chk")
-                            .with_extension(std::env::consts::EXE_EXTENSION),
-            "diffArguments": "--ignore-space-change --text --brief",
-            "inputFileName": prob.name.clone() + ".in",
-            "outputFileName": prob.name.clone() + ".out",
-            "problemTitle": prob.title,
-            "taskType": match prob.problem_type{
-                ProblemType::Program => 0,
-                ProblemType::Output => 1,
-                ProblemType::Interactive => bail!("lemon 不支持交互题"),
-            },
-            "compilerConfiguration": compilers,
-            "testCases": cases
-        });
-
-        prob_jsons.push(prob_json);
     }
 
     // 组装全局 JSON
-
     let day_cdf = json!({
         "contestTitle": day.name,
         "contestants": Value::Array(Vec::new()),
-        "tasks": prob_jsons
+        "tasks": prob_jsons,
     });
 
     let cdf_file = output_dir.join(day.name.clone()).with_extension("cdf");
 
     fs::write(cdf_file, serde_json::to_string_pretty(&day_cdf)?)?;
 
-    warn!("受 Lemon 限制，您需要手动调整编译选项。");
-    warn!("目前设置是默认(default)，如需要请自行修改。");
+    // Lemon 的编译配置保存在其设置中，而不是 cdf 文件中，只能提示手动添加
+    if !day.compile.is_empty() {
+        warn!(
+            "Lemon 的编译配置保存在其设置中，请手动将各编译器的 {} 配置修改为以下编译选项：",
+            CONFIGURATION_NAME
+        );
+        for (lang, flags) in &day.compile {
+            warn!("  {}: {}", lemon_compiler(lang)?, flags);
+        }
+    }
+
+    if !downgrades.is_empty() {
+        warn!("以下设置无法在 Lemon 中完整表示：");
+        for downgrade in &downgrades {
+            warn!("  {}", downgrade);
+        }
+        fs::write(
+            output_dir.join("downgrade.txt"),
+            downgrades.join("\n") + "\n",
+        )?;
+    }
 
     Ok(())
 }
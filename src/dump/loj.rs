@@ -0,0 +1,166 @@
+use super::{Failure, attachments, render_statement, try_problem, zip_write};
+use crate::prelude::*;
+use zip::ZipWriter;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataYml {
+    subtasks: Vec<Subtask>,
+    input_file: String,
+    output_file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    special_judge: Option<SourceFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interactor: Option<SourceFile>,
+}
+
+#[derive(Debug, Serialize)]
+struct Subtask {
+    score: u32,
+    #[serde(rename = "type")]
+    subtask_type: String,
+    cases: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceFile {
+    language: String,
+    file_name: String,
+}
+
+/// 导出一道题目为 LOJ（SYZOJ）的数据包、题面与附加文件
+fn dump_problem(prob: &ProblemConfig, day: &ContestDayConfig, output_dir: &Path) -> Result<()> {
+    let file = fs::File::create(output_dir.join(format!("{}.zip", prob.name)))?;
+    let mut zip = ZipWriter::new(file);
+
+    // 测试数据按子任务顺序编号
+    let mut subtasks = Vec::new();
+    let mut idx = 0;
+    for task in prob.subtasks.values() {
+        let mut cases = Vec::new();
+        for case in &task.items {
+            idx += 1;
+            zip_write(
+                &mut zip,
+                &format!("{}.in", idx),
+                &fs::read(prob.path.join("data").join(&case.input))?,
+            )?;
+            zip_write(
+                &mut zip,
+                &format!("{}.ans", idx),
+                &fs::read(prob.path.join("data").join(&case.output))?,
+            )?;
+            cases.push(idx.to_string());
+        }
+        match task.policy {
+            // LOJ 将子任务的分数平均分给各测试点，求和的子任务中每个测试点各为一个子任务
+            ScorePolicy::Sum => {
+                for (case, name) in task.items.iter().zip(cases) {
+                    subtasks.push(Subtask {
+                        score: case.score,
+                        subtask_type: "sum".to_string(),
+                        cases: vec![name],
+                    });
+                }
+            }
+            ScorePolicy::Min => subtasks.push(Subtask {
+                score: task.max_score,
+                subtask_type: "min".to_string(),
+                cases,
+            }),
+            ScorePolicy::Max => bail!("loj 不支持 max 评分方法"),
+        }
+    }
+
+    let mut data_yml = DataYml {
+        subtasks,
+        input_file: "#.in".to_string(),
+        output_file: "#.ans".to_string(),
+        user_output: None,
+        special_judge: None,
+        interactor: None,
+    };
+
+    match prob.problem_type {
+        ProblemType::Program => (),
+        ProblemType::Output => data_yml.user_output = Some(format!("{}#.out", prob.name)),
+        ProblemType::Interactive => {
+            let interactor = prob
+                .path
+                .join("data")
+                .join("interactor")
+                .join("interactor.cpp");
+            if !interactor.exists() {
+                bail!(
+                    "题目 {} 为交互题，但找不到交互器 data/interactor/interactor.cpp",
+                    prob.name
+                );
+            }
+            zip_write(&mut zip, "interactor_cpp.cpp", &fs::read(interactor)?)?;
+            data_yml.interactor = Some(SourceFile {
+                language: "cpp17".to_string(),
+                file_name: "interactor_cpp.cpp".to_string(),
+            });
+        }
+    }
+
+    if prob.use_chk.unwrap_or(false) {
+        let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+        if !chk_path.exists() {
+            bail!("chk 文件不存在");
+        }
+        zip_write(&mut zip, "spj_cpp.cpp", &fs::read(chk_path)?)?;
+        data_yml.special_judge = Some(SourceFile {
+            language: "cpp17".to_string(),
+            file_name: "spj_cpp.cpp".to_string(),
+        });
+    }
+
+    zip_write(
+        &mut zip,
+        "data.yml",
+        serde_yaml::to_string(&data_yml)?.as_bytes(),
+    )?;
+    zip.finish()?;
+
+    // 题面
+    fs::write(
+        output_dir.join(format!("{}.md", prob.name)),
+        render_statement(prob, day, None)?,
+    )?;
+
+    // 附加文件
+    let files = attachments(prob)?;
+    if !files.is_empty() {
+        let file = fs::File::create(output_dir.join(format!("{}_down.zip", prob.name)))?;
+        let mut zip = ZipWriter::new(file);
+        for (name, path) in files {
+            zip_write(&mut zip, &name, &fs::read(path)?)?;
+        }
+        zip.finish()?;
+    }
+
+    if prob.file_io.unwrap_or(false) {
+        warn!("题目 {} 使用文件输入输出，请在 LOJ 中手动设置", prob.name);
+    }
+    info!(
+        "题目 {}: 时间限制 {} ms，空间限制 {} MiB，请在 LOJ 中手动设置",
+        prob.name,
+        (prob.time_limit * 1000.0) as u32,
+        prob.memory_limit.as_mib() as u32
+    );
+
+    Ok(())
+}
+
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || dump_problem(prob, day, output_dir));
+    }
+
+    Ok(())
+}
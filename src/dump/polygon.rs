@@ -0,0 +1,533 @@
+use super::{Failure, statement_ast, try_problem};
+use crate::prelude::*;
+use crate::ren::renderers::latex::render_body_with;
+use crate::ren::utils::inline_text;
+use crate::test::check_test_case;
+use markdown_ppp::ast::{Block, Document, Inline};
+use quick_xml::se::Serializer;
+
+#[derive(Debug, Serialize)]
+struct PolygonProblem {
+    #[serde(rename = "@revision")]
+    revision: u32,
+    #[serde(rename = "@short-name")]
+    short_name: String,
+    names: Names,
+    judging: Judging,
+    files: Files,
+    assets: Assets,
+}
+
+#[derive(Debug, Serialize)]
+struct Names {
+    name: Vec<Name>,
+}
+
+#[derive(Debug, Serialize)]
+struct Name {
+    #[serde(rename = "@language")]
+    language: String,
+    #[serde(rename = "@value")]
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Judging {
+    #[serde(rename = "@input-file")]
+    input_file: String,
+    #[serde(rename = "@output-file")]
+    output_file: String,
+    testset: Testset,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Testset {
+    #[serde(rename = "@name")]
+    name: String,
+    time_limit: u64,
+    memory_limit: u64,
+    test_count: usize,
+    input_path_pattern: String,
+    answer_path_pattern: String,
+    tests: Tests,
+    groups: Groups,
+}
+
+#[derive(Debug, Serialize)]
+struct Tests {
+    test: Vec<Test>,
+}
+
+#[derive(Debug, Serialize)]
+struct Test {
+    #[serde(rename = "@method")]
+    method: String,
+    #[serde(rename = "@sample", skip_serializing_if = "Option::is_none")]
+    sample: Option<bool>,
+    #[serde(rename = "@group")]
+    group: String,
+    #[serde(rename = "@points")]
+    points: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct Groups {
+    group: Vec<Group>,
+}
+
+#[derive(Debug, Serialize)]
+struct Group {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@points")]
+    points: u32,
+    #[serde(rename = "@points-policy")]
+    points_policy: String,
+    #[serde(rename = "@feedback-policy")]
+    feedback_policy: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Files {
+    resources: Resources,
+    executables: Executables,
+}
+
+#[derive(Debug, Serialize)]
+struct Resources {
+    file: Vec<Source>,
+}
+
+#[derive(Debug, Serialize)]
+struct Executables {
+    executable: Vec<WithSource>,
+}
+
+#[derive(Debug, Serialize)]
+struct Source {
+    #[serde(rename = "@path")]
+    path: String,
+    #[serde(rename = "@type")]
+    source_type: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WithSource {
+    source: Source,
+}
+
+#[derive(Debug, Serialize)]
+struct Assets {
+    checker: Checker,
+    validators: Validators,
+    solutions: Solutions,
+}
+
+#[derive(Debug, Serialize)]
+struct Checker {
+    #[serde(rename = "@type")]
+    checker_type: String,
+    source: Source,
+}
+
+#[derive(Debug, Serialize)]
+struct Validators {
+    validator: Vec<WithSource>,
+}
+
+#[derive(Debug, Serialize)]
+struct Solutions {
+    solution: Vec<Solution>,
+}
+
+#[derive(Debug, Serialize)]
+struct Solution {
+    #[serde(rename = "@tag")]
+    tag: String,
+    source: Source,
+}
+
+/// 复制文件，目标目录不存在时自动创建
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("复制 {} 到 {} 失败", from.display(), to.display()))?;
+    Ok(())
+}
+
+/// 根据扩展名得到 Polygon 的源文件类型
+fn source_type(path: &Path) -> String {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("c") => "c.gcc",
+        Some("py") => "python.3",
+        Some("java") => "java11",
+        Some("pas") => "pas.fpc",
+        Some("h") => "h.g++",
+        _ => "cpp.g++17",
+    }
+    .to_string()
+}
+
+/// 根据期望得分得到 Polygon 的解法标签，返回 `None` 表示无法判断
+fn solution_tag(name: &str, test: &TestCase) -> Option<&'static str> {
+    let full = check_test_case(test, 100);
+    let partial = (0..100).any(|score| check_test_case(test, score));
+    match (full, partial) {
+        (true, false) if name == "std" => Some("main"),
+        (true, false) => Some("accepted"),
+        (true, true) => Some("time-limit-exceeded-or-accepted"),
+        (false, true) if name.to_lowercase().contains("tle") => Some("time-limit-exceeded"),
+        (false, true) => Some("wrong-answer"),
+        (false, false) => None,
+    }
+}
+
+/// 按标题将题面拆分为 Polygon 的题面片段
+///
+/// 样例由 example 文件提供，因此样例相关的段落会被丢弃。
+fn split_statement(statement: Document) -> BTreeMap<&'static str, Vec<Block>> {
+    let mut sections: BTreeMap<&'static str, Vec<Block>> = BTreeMap::new();
+    let mut current = "legend";
+    for block in statement.blocks {
+        if let Block::Heading(heading) = &block {
+            let title = inline_text(&heading.content);
+            let title = title.trim();
+            current = if title.contains("样例") {
+                ""
+            } else if title.contains("输入") {
+                "input"
+            } else if title.contains("输出") {
+                "output"
+            } else if title.contains("数据范围") || title.contains("子任务") {
+                "scoring"
+            } else if title.contains("提示") || title.contains("说明") {
+                "notes"
+            } else if title.contains("描述") || title.contains("背景") {
+                "legend"
+            } else {
+                // 无法识别的标题以粗体保留在提示中
+                sections
+                    .entry("notes")
+                    .or_default()
+                    .push(Block::Paragraph(vec![Inline::Strong(
+                        heading.content.clone(),
+                    )]));
+                "notes"
+            };
+            continue;
+        }
+        if current.is_empty() {
+            continue;
+        }
+        sections.entry(current).or_default().push(block);
+    }
+    sections
+}
+
+/// 将题面片段渲染为 LaTeX，Codeforces 使用 `$$$` 作为行内公式分隔符
+fn render_section(blocks: Vec<Block>) -> String {
+    render_body_with(&Document { blocks }, |formula, display| {
+        if display {
+            format!("$$$$$${}$$$$$$", formula)
+        } else {
+            format!("$$${}$$$", formula)
+        }
+    })
+}
+
+/// 导出一道题目为 Polygon 题目包
+fn dump_problem(prob: &ProblemConfig, day: &ContestDayConfig, output_dir: &Path) -> Result<()> {
+    let prob_dir = output_dir.join(&prob.name);
+    fs::create_dir(&prob_dir)?;
+
+    if !matches!(prob.problem_type, ProblemType::Program) {
+        warn!("目前只支持导出传统题，题目 {} 将按传统题导出", prob.name);
+    }
+
+    let mut tests = Vec::new();
+    let mut groups = Vec::new();
+    let mut index = 0;
+
+    // 样例放在最前，单独成组且不计分
+    let samples: Vec<&SampleItem> = prob
+        .samples
+        .iter()
+        .filter(|sample| sample.input.get().is_some() && sample.output.get().is_some())
+        .collect();
+    for sample in &samples {
+        index += 1;
+        copy_file(
+            &prob.path.join("sample").join(sample.input.get().unwrap()),
+            &prob_dir.join("tests").join(format!("{:02}", index)),
+        )?;
+        copy_file(
+            &prob.path.join("sample").join(sample.output.get().unwrap()),
+            &prob_dir.join("tests").join(format!("{:02}.a", index)),
+        )?;
+        tests.push(Test {
+            method: "manual".to_string(),
+            sample: Some(true),
+            group: "samples".to_string(),
+            points: 0,
+        });
+    }
+    if !samples.is_empty() {
+        groups.push(Group {
+            name: "samples".to_string(),
+            points: 0,
+            points_policy: "each-test".to_string(),
+            feedback_policy: "complete".to_string(),
+        });
+    }
+
+    for (id, task) in &prob.subtasks {
+        let (points, policy): (Vec<u32>, &str) = match task.policy {
+            ScorePolicy::Sum => (
+                task.items.iter().map(|case| case.score).collect(),
+                "each-test",
+            ),
+            ScorePolicy::Min | ScorePolicy::Max => {
+                if matches!(task.policy, ScorePolicy::Max) {
+                    warn!(
+                        "Polygon 不支持 max 评分方法，题目 {} 的子任务 {} 将按整组通过计分",
+                        prob.name, id
+                    );
+                }
+                // 整组通过时得到各测试点分数之和
+                let count = task.items.len() as u32;
+                (
+                    (0..count)
+                        .map(|i| task.max_score / count + u32::from(i < task.max_score % count))
+                        .collect(),
+                    "complete-group",
+                )
+            }
+        };
+
+        for (case, points) in task.items.iter().zip(points) {
+            index += 1;
+            copy_file(
+                &prob.path.join("data").join(&case.input),
+                &prob_dir.join("tests").join(format!("{:02}", index)),
+            )?;
+            copy_file(
+                &prob.path.join("data").join(&case.output),
+                &prob_dir.join("tests").join(format!("{:02}.a", index)),
+            )?;
+            tests.push(Test {
+                method: "manual".to_string(),
+                sample: None,
+                group: id.to_string(),
+                points,
+            });
+        }
+        groups.push(Group {
+            name: id.to_string(),
+            points: task.max_score,
+            points_policy: policy.to_string(),
+            feedback_policy: "complete".to_string(),
+        });
+    }
+
+    // testlib
+    let mut resources = Vec::new();
+    let testlib = get_context()
+        .assets_dirs
+        .iter()
+        .map(|dir| dir.join("checkers").join("testlib.h"))
+        .chain([prob.path.join("data").join("chk").join("testlib.h")])
+        .find(|path| path.exists());
+    match testlib {
+        Some(testlib) => {
+            copy_file(&testlib, &prob_dir.join("files").join("testlib.h"))?;
+            resources.push(Source {
+                path: "files/testlib.h".to_string(),
+                source_type: "h.g++".to_string(),
+            });
+        }
+        None => warn!("找不到 testlib.h，请手动添加到题目包中"),
+    }
+
+    // 检查器，未使用 chk 时使用内置的默认检查器
+    let checker = if prob.use_chk.unwrap_or(false) {
+        let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+        if !chk_path.exists() {
+            bail!("chk 文件不存在");
+        }
+        chk_path
+    } else {
+        get_context()
+            .assets_dirs
+            .iter()
+            .map(|dir| dir.join("checkers").join("normal.cpp"))
+            .find(|path| path.exists())
+            .context("找不到默认检查器 normal.cpp")?
+    };
+    copy_file(&checker, &prob_dir.join("files").join("check.cpp"))?;
+
+    // 校验器与生成器
+    let mut validators = Vec::new();
+    let mut executables = Vec::new();
+    for (dir, is_validator) in [("val", true), ("gen", false)] {
+        let src_dir = prob.path.join(dir);
+        if !src_dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&src_dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            if !path.is_file() || file_name == "testlib.h" {
+                continue;
+            }
+            let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+            if !["cpp", "cc", "c", "py"].contains(&ext) {
+                continue;
+            }
+            let target = format!("files/{}", file_name);
+            copy_file(&path, &prob_dir.join(&target))?;
+            let source = WithSource {
+                source: Source {
+                    path: target,
+                    source_type: source_type(&path),
+                },
+            };
+            if is_validator {
+                validators.push(source);
+            } else {
+                executables.push(source);
+            }
+        }
+    }
+    if !executables.is_empty() {
+        warn!(
+            "dmk 生成器的调用方式与 Polygon 不同，题目 {} 的生成器需要手动编写脚本",
+            prob.name
+        );
+    }
+
+    // 解法
+    let mut solutions = Vec::new();
+    for (name, test) in &prob.tests {
+        let Some(tag) = solution_tag(name, test) else {
+            warn!("无法根据期望得分判断 {} 的标签，已跳过", name);
+            continue;
+        };
+        let path = prob.path.join(&test.path);
+        if !path.is_file() {
+            warn!("解法 {} 不是单个文件，已跳过", name);
+            continue;
+        }
+        let target = format!("solutions/{}", path.file_name().unwrap().to_string_lossy());
+        copy_file(&path, &prob_dir.join(&target))?;
+        solutions.push(Solution {
+            tag: tag.to_string(),
+            source: Source {
+                path: target,
+                source_type: source_type(&path),
+            },
+        });
+    }
+    // 主解法放在最前
+    solutions.sort_by_key(|solution| solution.tag != "main");
+
+    // 题面
+    let sections_dir = prob_dir.join("statement-sections").join("chinese");
+    fs::create_dir_all(&sections_dir)?;
+    fs::write(sections_dir.join("name.tex"), &prob.title)?;
+    for (section, blocks) in split_statement(statement_ast(prob, day, None)?) {
+        fs::write(
+            sections_dir.join(format!("{}.tex", section)),
+            render_section(blocks).trim(),
+        )?;
+    }
+    for (index, sample) in samples.iter().enumerate() {
+        copy_file(
+            &prob.path.join("sample").join(sample.input.get().unwrap()),
+            &sections_dir.join(format!("example.{:02}", index + 1)),
+        )?;
+        copy_file(
+            &prob.path.join("sample").join(sample.output.get().unwrap()),
+            &sections_dir.join(format!("example.{:02}.a", index + 1)),
+        )?;
+    }
+
+    let file_io = prob.file_io.unwrap_or(false);
+    let problem = PolygonProblem {
+        revision: 1,
+        short_name: prob.name.clone(),
+        names: Names {
+            name: vec![Name {
+                language: "chinese".to_string(),
+                value: prob.title.clone(),
+            }],
+        },
+        judging: Judging {
+            input_file: if file_io {
+                format!("{}.in", prob.name)
+            } else {
+                String::new()
+            },
+            output_file: if file_io {
+                format!("{}.out", prob.name)
+            } else {
+                String::new()
+            },
+            testset: Testset {
+                name: "tests".to_string(),
+                time_limit: (prob.time_limit * 1000.0) as u64,
+                memory_limit: prob.memory_limit.as_u64(),
+                test_count: tests.len(),
+                input_path_pattern: "tests/%02d".to_string(),
+                answer_path_pattern: "tests/%02d.a".to_string(),
+                tests: Tests { test: tests },
+                groups: Groups { group: groups },
+            },
+        },
+        files: Files {
+            resources: Resources { file: resources },
+            executables: Executables {
+                executable: executables,
+            },
+        },
+        assets: Assets {
+            checker: Checker {
+                checker_type: "testlib".to_string(),
+                source: Source {
+                    path: "files/check.cpp".to_string(),
+                    source_type: "cpp.g++17".to_string(),
+                },
+            },
+            validators: Validators {
+                validator: validators,
+            },
+            solutions: Solutions {
+                solution: solutions,
+            },
+        },
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"no\"?>\n");
+    let mut serializer = Serializer::with_root(&mut xml, Some("problem"))?;
+    serializer.indent(' ', 4);
+    problem.serialize(serializer)?;
+    fs::write(prob_dir.join("problem.xml"), xml)?;
+
+    Ok(())
+}
+
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || dump_problem(prob, day, output_dir));
+    }
+
+    Ok(())
+}
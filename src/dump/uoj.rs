@@ -0,0 +1,178 @@
+use super::{Failure, try_problem};
+use crate::prelude::*;
+
+/// 写入 UOJ 的 problem.conf，每行为以空格分隔的键与值
+fn write_conf(path: &Path, conf: &[(String, String)]) -> Result<()> {
+    let content: String = conf
+        .iter()
+        .map(|(key, value)| format!("{} {}\n", key, value))
+        .collect();
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 复制文件并附带路径信息
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    fs::copy(from, to)
+        .with_context(|| format!("复制 {} 到 {} 失败", from.display(), to.display()))?;
+    Ok(())
+}
+
+/// 导出一道题目为 UOJ 的数据目录
+fn dump_problem(prob: &ProblemConfig, output_dir: &Path) -> Result<()> {
+    let prob_dir = output_dir.join(&prob.name);
+    fs::create_dir(&prob_dir)?;
+
+    let mut conf = vec![("use_builtin_judger".to_string(), "on".to_string())];
+
+    match prob.problem_type {
+        ProblemType::Program => (),
+        ProblemType::Output => conf.push(("submit_answer".to_string(), "on".to_string())),
+        ProblemType::Interactive => {
+            let interactor = prob
+                .path
+                .join("data")
+                .join("interactor")
+                .join("interactor.cpp");
+            if !interactor.exists() {
+                bail!(
+                    "题目 {} 为交互题，但找不到交互器 data/interactor/interactor.cpp",
+                    prob.name
+                );
+            }
+            copy_file(&interactor, &prob_dir.join("interactor.cpp"))?;
+            conf.push(("interaction_mode".to_string(), "on".to_string()));
+        }
+    }
+
+    // 测试数据按子任务顺序编号
+    let mut cases = Vec::new();
+    for task in prob.subtasks.values() {
+        cases.extend(task.items.iter().cloned());
+    }
+    for (idx, case) in cases.iter().enumerate() {
+        copy_file(
+            &prob.path.join("data").join(&case.input),
+            &prob_dir.join(format!("{}{}.in", prob.name, idx + 1)),
+        )?;
+        copy_file(
+            &prob.path.join("data").join(&case.output),
+            &prob_dir.join(format!("{}{}.ans", prob.name, idx + 1)),
+        )?;
+    }
+
+    // 样例作为额外测试
+    let mut n_samples = 0;
+    for sample in &prob.samples {
+        let (Some(input), Some(output)) = (sample.input.get(), sample.output.get()) else {
+            warn!("题目 {} 的样例 {} 缺少文件，已跳过", prob.name, sample.id);
+            continue;
+        };
+        n_samples += 1;
+        copy_file(
+            &prob.path.join("sample").join(input),
+            &prob_dir.join(format!("ex_{}{}.in", prob.name, n_samples)),
+        )?;
+        copy_file(
+            &prob.path.join("sample").join(output),
+            &prob_dir.join(format!("ex_{}{}.ans", prob.name, n_samples)),
+        )?;
+    }
+
+    conf.extend([
+        ("n_tests".to_string(), cases.len().to_string()),
+        ("n_ex_tests".to_string(), n_samples.to_string()),
+        ("n_sample_tests".to_string(), n_samples.to_string()),
+        ("input_pre".to_string(), prob.name.clone()),
+        ("input_suf".to_string(), "in".to_string()),
+        ("output_pre".to_string(), prob.name.clone()),
+        ("output_suf".to_string(), "ans".to_string()),
+    ]);
+
+    if prob.time_limit.fract() != 0.0 {
+        warn!(
+            "UOJ 的时间限制只能为整数秒，题目 {} 的时间限制将向上取整",
+            prob.name
+        );
+    }
+    conf.extend([
+        (
+            "time_limit".to_string(),
+            (prob.time_limit.ceil() as u32).to_string(),
+        ),
+        (
+            "memory_limit".to_string(),
+            (prob.memory_limit.as_mib() as u32).to_string(),
+        ),
+        ("output_limit".to_string(), "64".to_string()),
+    ]);
+
+    // 全部为求和的子任务时直接设置每个测试点的分数，
+    // 否则求和的子任务中每个测试点各为一个 UOJ 子任务
+    if prob
+        .subtasks
+        .values()
+        .all(|task| matches!(task.policy, ScorePolicy::Sum))
+    {
+        for (idx, case) in cases.iter().enumerate() {
+            conf.push((format!("point_score_{}", idx + 1), case.score.to_string()));
+        }
+    } else {
+        let mut subtasks = Vec::new();
+        let mut end = 0;
+        for task in prob.subtasks.values() {
+            match task.policy {
+                ScorePolicy::Sum => {
+                    for case in &task.items {
+                        end += 1;
+                        subtasks.push((end, case.score, None));
+                    }
+                }
+                ScorePolicy::Min => {
+                    end += task.items.len();
+                    let subtask_type = if prob.partial_score { "min" } else { "packed" };
+                    subtasks.push((end, task.max_score, Some(subtask_type)));
+                }
+                ScorePolicy::Max => bail!("uoj 不支持 max 评分方法"),
+            }
+        }
+
+        conf.push(("n_subtasks".to_string(), subtasks.len().to_string()));
+        for (idx, (end, score, subtask_type)) in subtasks.iter().enumerate() {
+            conf.push((format!("subtask_end_{}", idx + 1), end.to_string()));
+            conf.push((format!("subtask_score_{}", idx + 1), score.to_string()));
+            if let Some(subtask_type) = subtask_type {
+                conf.push((
+                    format!("subtask_type_{}", idx + 1),
+                    subtask_type.to_string(),
+                ));
+            }
+        }
+    }
+
+    // 检查器
+    if prob.use_chk.unwrap_or(false) {
+        let chk_path = prob.path.join("data").join("chk").join("chk.cpp");
+        if !chk_path.exists() {
+            bail!("chk 文件不存在");
+        }
+        copy_file(&chk_path, &prob_dir.join("chk.cpp"))?;
+    } else {
+        conf.push(("use_builtin_checker".to_string(), "wcmp".to_string()));
+    }
+
+    write_conf(&prob_dir.join("problem.conf"), &conf)?;
+
+    Ok(())
+}
+
+pub fn main(day: &ContestDayConfig, output_dir: &Path, failures: &mut Vec<Failure>) -> Result<()> {
+    for prob in day.subconfig.values() {
+        info!("导出题目: {}", prob.name);
+        try_problem(failures, day, prob, || dump_problem(prob, output_dir));
+    }
+
+    info!("请将各题目的目录上传到 UOJ 的题目数据中");
+
+    Ok(())
+}
@@ -1,4 +1,3 @@
-use crate::config::SingleDataItem;
 use crate::config::load_contest_config;
 use crate::config::load_day_config;
 use crate::config::load_problem_config;
@@ -13,11 +12,15 @@ use clap::Subcommand;
 use clap_complete::Shell;
 use dialoguer::Select;
 use dialoguer::theme::ColorfulTheme;
+use import::ImportArgs;
 use indexmap::IndexMap;
 use natord::compare;
 use regex::Regex;
 use std::io;
 
+mod data;
+mod import;
+
 const CONFIG_FILE_NAME: &str = "conf.json";
 
 #[derive(Debug, Clone, Subcommand)]
@@ -36,7 +39,7 @@ pub enum Targets {
 
     /// 自动检测数据
     #[command(version, alias = "t")]
-    Data(GenConfirmArgs),
+    Data(GenDataArgs),
     /// 自动检测样例
     #[command(version, alias = "s")]
     Samples(GenConfirmArgs),
@@ -47,6 +50,10 @@ pub enum Targets {
     #[command(version, alias = "a")]
     All(GenConfirmArgs),
 
+    /// 从其他格式导入
+    #[command(version, alias = "i")]
+    Import(ImportArgs),
+
     /// 生成 lfs 文件
     #[command(version)]
     Lfs,
@@ -72,6 +79,23 @@ pub struct GenConfirmArgs {
     confirm: bool,
 }
 
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct GenDataArgs {
+    /// 跳过确认提示
+    #[arg(short = 'y')]
+    confirm: bool,
+
+    /// 数据命名方案，为包含 subtask 与可选 id 命名捕获组的正则表达式，
+    /// 匹配相对于 data 目录且不含 .in 后缀的路径
+    #[arg(long)]
+    scheme: Option<String>,
+
+    /// 子任务计分方式，默认检测到子任务时取最小值，否则求和
+    #[arg(long, value_enum)]
+    policy: Option<ScorePolicy>,
+}
+
 #[derive(Args, Debug, Clone)]
 #[command(version)]
 pub struct GenCompleteArgs {
@@ -91,24 +115,28 @@ pub struct GenArgs {
 fn gen_contest(args: GenStatementArgs) -> Result<()> {
     let current_dir = std::env::current_dir()?;
 
+    for contest_name in &args.name {
+        new_contest(&current_dir, contest_name)?;
+    }
+    Ok(())
+}
+
+/// 从 scaffold 创建竞赛
+fn new_contest(dir: &Path, contest_name: &str) -> Result<PathBuf> {
     // 查找scaffold/contest目录（在程序上下文中的列表中第一个存在的）
     let scaffold_path = find_in_scaffold("contest", true)?;
+    let contest_dir = dir.join(contest_name);
 
-    for contest_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(contest_name))?;
+    copy_dir_recursive(&scaffold_path, &contest_dir)?;
 
-        let mut contest_json: ContestConfig =
-            load_contest_config(&current_dir.join(contest_name).join(CONFIG_FILE_NAME))?;
+    let mut contest_json: ContestConfig = load_contest_config(&contest_dir.join(CONFIG_FILE_NAME))?;
 
-        contest_json.name = contest_name.to_string();
+    contest_json.name = contest_name.to_string();
 
-        let updated_content = save_contest_config(&contest_json)?;
-        std::fs::write(
-            current_dir.join(contest_name).join(CONFIG_FILE_NAME),
-            updated_content,
-        )?;
-    }
-    Ok(())
+    let updated_content = save_contest_config(&contest_json)?;
+    std::fs::write(contest_dir.join(CONFIG_FILE_NAME), updated_content)?;
+
+    Ok(contest_dir)
 }
 
 fn gen_day(args: GenStatementArgs) -> Result<()> {
@@ -133,37 +161,43 @@ fn gen_day(args: GenStatementArgs) -> Result<()> {
         bail!("无效的配置文件");
     }
 
+    for day_name in &args.name {
+        new_day(&current_dir, day_name)?;
+    }
+
+    Ok(())
+}
+
+/// 在竞赛目录下从 scaffold 创建竞赛日，并更新竞赛配置文件的 subdir 字段
+fn new_day(contest_dir: &Path, day_name: &str) -> Result<PathBuf> {
     // 查找scaffold/day目录（在程序上下文中的列表中第一个存在的）
     let scaffold_path = find_in_scaffold("day", true)?;
+    let day_dir = contest_dir.join(day_name);
 
-    for day_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(day_name))?;
+    copy_dir_recursive(&scaffold_path, &day_dir)?;
 
-        let mut day_json: ContestDayConfig =
-            load_day_config(&current_dir.join(day_name).join(CONFIG_FILE_NAME))?;
+    let mut day_json: ContestDayConfig = load_day_config(&day_dir.join(CONFIG_FILE_NAME))?;
 
-        day_json.name = day_name.to_string();
+    day_json.name = day_name.to_string();
 
-        let updated_content = save_day_config(&day_json)?;
-        std::fs::write(
-            current_dir.join(day_name).join(CONFIG_FILE_NAME),
-            updated_content,
-        )?;
-    }
+    let updated_content = save_day_config(&day_json)?;
+    std::fs::write(day_dir.join(CONFIG_FILE_NAME), updated_content)?;
 
-    // 更新contest配置文件的subdir字段
-    let mut contest_config: serde_json::Value = serde_json::from_str(&content)?;
-    if let Some(subdir) = contest_config
-        .get_mut("subdir")
-        .and_then(|v| v.as_array_mut())
-    {
-        for day_name in &args.name {
-            subdir.push(serde_json::Value::String(day_name.clone()));
-        }
+    append_subdir(&contest_dir.join(CONFIG_FILE_NAME), day_name)?;
+
+    Ok(day_dir)
+}
+
+/// 向上级配置文件的 subdir 字段追加子目录
+fn append_subdir(config_path: &Path, name: &str) -> Result<()> {
+    let mut config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(config_path)?)?;
+    if let Some(subdir) = config.get_mut("subdir").and_then(|v| v.as_array_mut()) {
+        subdir.push(serde_json::Value::String(name.to_string()));
     }
 
-    let updated_content = serde_json::to_string_pretty(&contest_config)?;
-    std::fs::write(&config_path, updated_content)?;
+    let updated_content = serde_json::to_string_pretty(&config)?;
+    std::fs::write(config_path, updated_content)?;
     Ok(())
 }
 
@@ -189,35 +223,31 @@ fn gen_problem(args: GenStatementArgs) -> Result<()> {
         bail!("无效的配置文件");
     }
 
+    for problem_name in &args.name {
+        new_problem(&current_dir, problem_name)?;
+    }
+
+    Ok(())
+}
+
+/// 在竞赛日目录下从 scaffold 创建题目，并更新竞赛日配置文件的 subdir 字段
+fn new_problem(day_dir: &Path, problem_name: &str) -> Result<PathBuf> {
     // 查找scaffold/problem目录（在程序上下文中的列表中第一个存在的）
     let scaffold_path = find_in_scaffold("problem", true)?;
+    let problem_dir = day_dir.join(problem_name);
 
-    for problem_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(problem_name))?;
+    copy_dir_recursive(&scaffold_path, &problem_dir)?;
 
-        let mut problem_json: ProblemConfig =
-            load_problem_config(&current_dir.join(problem_name).join(CONFIG_FILE_NAME))?;
+    let mut problem_json: ProblemConfig = load_problem_config(&problem_dir.join(CONFIG_FILE_NAME))?;
 
-        problem_json.name = problem_name.to_string();
+    problem_json.name = problem_name.to_string();
 
-        let updated_content = save_problem_config(&problem_json)?;
-        std::fs::write(
-            current_dir.join(problem_name).join(CONFIG_FILE_NAME),
-            updated_content,
-        )?;
-    }
+    let updated_content = save_problem_config(&problem_json)?;
+    std::fs::write(problem_dir.join(CONFIG_FILE_NAME), updated_content)?;
 
-    // 更新day配置文件的subdir字段
-    let mut day_config: serde_json::Value = serde_json::from_str(&content)?;
-    if let Some(subdir) = day_config.get_mut("subdir").and_then(|v| v.as_array_mut()) {
-        for problem_name in &args.name {
-            subdir.push(serde_json::Value::String(problem_name.clone()));
-        }
-    }
+    append_subdir(&day_dir.join(CONFIG_FILE_NAME), problem_name)?;
 
-    let updated_content = serde_json::to_string_pretty(&day_config)?;
-    std::fs::write(&config_path, updated_content)?;
-    Ok(())
+    Ok(problem_dir)
 }
 
 fn gen_lfs() -> Result<()> {
@@ -296,7 +326,7 @@ fn confirm_overwrite() -> bool {
     selection == 1
 }
 
-fn gen_data(args: GenConfirmArgs) -> Result<()> {
+fn gen_data(args: GenDataArgs) -> Result<()> {
     if !args.confirm && !confirm_overwrite() {
         return Ok(());
     }
@@ -325,41 +355,14 @@ fn gen_data(args: GenConfirmArgs) -> Result<()> {
                 warn!("题目 {} 不存在 data 目录，跳过数据生成", problem.name);
                 continue;
             }
-            let mut datas_entrys = Vec::<String>::new();
-            for entry in fs::read_dir(&data_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.is_file()
-                    && let Some(ext) = path.extension()
-                    && ext == "in"
-                {
-                    let output_file = path.file_stem().unwrap().to_string_lossy() + ".ans";
-                    let output_path = data_dir.join(output_file.as_ref());
-
-                    if output_path.exists() {
-                        datas_entrys.push(path.file_stem().unwrap().to_string_lossy().to_string());
-                    }
-                }
-            }
-            datas_entrys.sort_by(|a, b| compare(a, b));
-            let count = datas_entrys.len() as u32;
-
-            let datas: Vec<DataItem> = datas_entrys
-                .into_iter()
-                .enumerate()
-                .map(|(id, name)| {
-                    DataItem::Single(SingleDataItem {
-                        id: id as u32 + 1,
-                        input: Optional::initialized(format!("{}.in", name)),
-                        output: Optional::initialized(format!("{}.ans", name)),
-                        score: 100 / count,
-                        subtask: 0,
-                        args: HashMap::new(),
-                        manual: None,
-                    })
-                })
-                .collect();
-            let subtasks: BTreeMap<u32, ScorePolicy> = BTreeMap::from([(0, ScorePolicy::Sum)]);
+            let (datas, subtasks) =
+                data::detect_data(&data_dir, args.scheme.as_deref(), args.policy)?;
+            info!(
+                "题目 {} 检测到 {} 个数据点，{} 个子任务",
+                problem.name,
+                datas.len(),
+                subtasks.len()
+            );
 
             let mut now_problem = load_problem_config(&problem.path.join(CONFIG_FILE_NAME))?;
             now_problem.orig_data = datas;
@@ -552,7 +555,11 @@ fn gen_all(args: GenConfirmArgs) -> Result<()> {
     if !args.confirm && !confirm_overwrite() {
         return Ok(());
     }
-    gen_data(GenConfirmArgs { confirm: true })?;
+    gen_data(GenDataArgs {
+        confirm: true,
+        scheme: None,
+        policy: None,
+    })?;
     gen_sample(GenConfirmArgs { confirm: true })?;
     gen_code(GenConfirmArgs { confirm: true })?;
 
@@ -584,6 +591,7 @@ pub fn main(args: GenArgs) -> Result<()> {
         Targets::Samples(args) => gen_sample(args)?,
         Targets::All(args) => gen_all(args)?,
         Targets::Code(args) => gen_code(args)?,
+        Targets::Import(args) => import::main(args)?,
         Targets::Lfs => gen_lfs()?,
         Targets::Complete(args) => gen_complete(args)?,
     }
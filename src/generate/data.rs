@@ -0,0 +1,178 @@
+use crate::config::SingleDataItem;
+use crate::prelude::*;
+use crate::utils::optional::Optional;
+use natord::compare;
+use regex::Regex;
+
+/// 内置的数据命名方案
+///
+/// 匹配对象为相对于 data 目录、去掉 `.in` 后缀并以 `/` 分隔的路径，
+/// 例如 `subtask2_05`、`2-05`、`sub2/05`、`group2/05`
+const BUILTIN_SCHEMES: [&str; 2] = [
+    r"^(?:subtask|sub|task|st)?_?(?P<subtask>\d+)[-_](?P<id>\d+)$",
+    r"^(?:subtask|sub|task|group|st)?_?(?P<subtask>\d+)/(?:.*?)(?P<id>\d+)$",
+];
+
+/// 检测到的数据文件
+struct DetectedFile {
+    name: String,
+    subtask: Option<u32>,
+    id: Option<u64>,
+}
+
+/// 递归查找 data 目录中成对存在的 `.in` 与 `.ans` 文件，返回不含后缀的相对路径
+fn find_data_files(data_dir: &Path, dir: &Path, result: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_data_files(data_dir, &path, result)?;
+        } else if let Some(ext) = path.extension()
+            && ext == "in"
+            && path.with_extension("ans").exists()
+        {
+            let name = path
+                .strip_prefix(data_dir)?
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            result.push(name);
+        }
+    }
+    Ok(())
+}
+
+fn match_scheme(scheme: &Regex, name: &str) -> Option<(u32, Option<u64>)> {
+    let caps = scheme.captures(name)?;
+    let subtask = caps.name("subtask")?.as_str().parse().ok()?;
+    let id = caps.name("id").and_then(|m| m.as_str().parse().ok());
+    Some((subtask, id))
+}
+
+/// 将 `total` 分尽量平均地分给 `count` 份，余数分给靠前的份
+pub(super) fn spread(total: u32, count: usize) -> Vec<u32> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let base = total / count as u32;
+    let rest = total as usize % count;
+    (0..count).map(|i| base + u32::from(i < rest)).collect()
+}
+
+/// 检测数据文件并按命名方案划分子任务
+///
+/// 未指定 `scheme` 时选用匹配数据最多的内置方案，全部不匹配则所有数据归入子任务 0。
+pub fn detect_data(
+    data_dir: &Path,
+    scheme: Option<&str>,
+    policy: Option<ScorePolicy>,
+) -> Result<(Vec<DataItem>, BTreeMap<u32, ScorePolicy>)> {
+    let mut names = Vec::new();
+    find_data_files(data_dir, data_dir, &mut names)?;
+
+    let schemes = match scheme {
+        Some(scheme) => {
+            let regex = Regex::new(scheme).context("数据命名方案不是合法的正则表达式")?;
+            if !regex.capture_names().any(|name| name == Some("subtask")) {
+                bail!("数据命名方案必须包含名为 subtask 的捕获组");
+            }
+            vec![regex]
+        }
+        None => BUILTIN_SCHEMES
+            .iter()
+            .map(|scheme| Regex::new(scheme).unwrap())
+            .collect(),
+    };
+
+    // 选择匹配数据最多的方案
+    let best = schemes
+        .iter()
+        .map(|regex| {
+            let matched = names
+                .iter()
+                .filter(|name| match_scheme(regex, name).is_some())
+                .count();
+            (regex, matched)
+        })
+        .max_by_key(|(_, matched)| *matched)
+        .filter(|(_, matched)| *matched > 0)
+        .map(|(regex, _)| regex);
+
+    let mut files: Vec<DetectedFile> = Vec::new();
+    match best {
+        Some(regex) => {
+            info!("使用数据命名方案: {}", regex.as_str());
+            for name in names {
+                match match_scheme(regex, &name) {
+                    Some((subtask, id)) => files.push(DetectedFile {
+                        name,
+                        subtask: Some(subtask),
+                        id,
+                    }),
+                    None => warn!("数据 {} 不符合命名方案，已跳过", name),
+                }
+            }
+        }
+        None => {
+            if scheme.is_some() {
+                warn!("没有数据符合指定的命名方案，所有数据将归入子任务 0");
+            }
+            files.extend(names.into_iter().map(|name| DetectedFile {
+                name,
+                subtask: None,
+                id: None,
+            }));
+        }
+    }
+
+    files.sort_by(|a, b| {
+        a.subtask
+            .cmp(&b.subtask)
+            .then(a.id.cmp(&b.id))
+            .then_with(|| compare(&a.name, &b.name))
+    });
+
+    let has_subtasks = files.iter().any(|file| file.subtask.is_some());
+    let policy = policy.unwrap_or(if has_subtasks {
+        ScorePolicy::Min
+    } else {
+        ScorePolicy::Sum
+    });
+
+    let mut groups: BTreeMap<u32, Vec<&DetectedFile>> = BTreeMap::new();
+    for file in &files {
+        groups
+            .entry(file.subtask.unwrap_or(0))
+            .or_default()
+            .push(file);
+    }
+
+    let mut datas = Vec::new();
+    let mut next_id = 1;
+    for ((subtask, group), subtask_score) in groups.iter().zip(spread(100, groups.len())) {
+        let scores = match policy {
+            ScorePolicy::Sum => spread(subtask_score, group.len()),
+            ScorePolicy::Max | ScorePolicy::Min => vec![subtask_score; group.len()],
+        };
+        for (file, score) in group.iter().zip(scores) {
+            datas.push(DataItem::Single(SingleDataItem {
+                id: next_id,
+                input: Optional::initialized(format!("{}.in", file.name)),
+                output: Optional::initialized(format!("{}.ans", file.name)),
+                score,
+                subtask: *subtask,
+                args: HashMap::new(),
+                manual: None,
+            }));
+            next_id += 1;
+        }
+    }
+
+    let mut subtasks: BTreeMap<u32, ScorePolicy> = groups.keys().map(|id| (*id, policy)).collect();
+    if subtasks.is_empty() {
+        subtasks.insert(0, policy);
+    }
+
+    Ok((datas, subtasks))
+}
@@ -0,0 +1,82 @@
+use crate::prelude::*;
+use clap::{Args, Subcommand};
+use lemon::ImportLemonArgs;
+use polygon::ImportPolygonArgs;
+
+mod lemon;
+mod polygon;
+
+#[derive(Debug, Clone, Subcommand)]
+#[command(version)]
+#[command(infer_subcommands = false)]
+pub enum ImportTargets {
+    /// 导入 Polygon 题目包
+    #[command(version)]
+    Polygon(ImportPolygonArgs),
+    /// 导入 Lemon 竞赛
+    #[command(version)]
+    Lemon(ImportLemonArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportArgs {
+    /// 导入的格式
+    #[command(subcommand)]
+    pub target: ImportTargets,
+}
+
+/// 检查当前目录是否为指定类型的目录，并返回该目录
+fn require_folder(folder: &str) -> Result<PathBuf> {
+    let current_dir = std::env::current_dir()?;
+    let config_path = current_dir.join(super::CONFIG_FILE_NAME);
+
+    if !config_path.exists() {
+        bail!("import命令必须在{}目录下执行", folder);
+    }
+
+    let json_value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path)?)?;
+    match json_value.get("folder").and_then(|v| v.as_str()) {
+        Some(now) if now == folder => Ok(current_dir),
+        Some(_) => bail!("import命令必须在{}目录下执行", folder),
+        None => bail!("无效的配置文件"),
+    }
+}
+
+/// 将 zip 包解压到临时目录，目录则直接使用，返回包的根目录
+///
+/// 第二个返回值为需要在导入完成后删除的临时目录。
+fn open_package(path: &Path) -> Result<(PathBuf, Option<PathBuf>)> {
+    if path.is_dir() {
+        return Ok((path.to_path_buf(), None));
+    }
+
+    let tmp_dir = std::env::temp_dir().join(format!("tuack-ng-import-{}", std::process::id()));
+    if tmp_dir.exists() {
+        fs::remove_dir_all(&tmp_dir)?;
+    }
+    fs::create_dir_all(&tmp_dir)?;
+
+    let file = fs::File::open(path).with_context(|| format!("无法打开 {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file).context("无法读取 zip 文件")?;
+    archive.extract(&tmp_dir).context("解压 zip 文件失败")?;
+
+    Ok((tmp_dir.clone(), Some(tmp_dir)))
+}
+
+/// 复制文件，目标目录不存在时自动创建
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("复制 {} 到 {} 失败", from.display(), to.display()))?;
+    Ok(())
+}
+
+pub fn main(args: ImportArgs) -> Result<()> {
+    match args.target {
+        ImportTargets::Polygon(args) => polygon::main(args),
+        ImportTargets::Lemon(args) => lemon::main(args),
+    }
+}
@@ -0,0 +1,256 @@
+use super::copy_file;
+use crate::config::{
+    SingleDataItem, load_contest_config, load_day_config, load_problem_config, save_contest_config,
+    save_day_config, save_problem_config,
+};
+use crate::generate::{CONFIG_FILE_NAME, new_contest, new_day, new_problem};
+use crate::prelude::*;
+use crate::utils::optional::Optional;
+use bytesize::ByteSize;
+use clap::Args;
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportLemonArgs {
+    /// Lemon 竞赛文件（.cdf），数据位于同目录的 data 文件夹中
+    #[arg(required = true)]
+    pub path: PathBuf,
+
+    /// 竞赛英文名称，默认使用 cdf 文件名
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// 竞赛日名称
+    #[arg(long, default_value = "day1")]
+    pub day: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LemonContest {
+    #[serde(default)]
+    contest_title: String,
+    #[serde(default)]
+    tasks: Vec<LemonTask>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LemonTask {
+    #[serde(default)]
+    problem_title: String,
+    #[serde(default)]
+    source_file_name: Option<String>,
+    #[serde(default)]
+    input_file_name: Option<String>,
+    #[serde(default)]
+    task_type: u32,
+    #[serde(default)]
+    comparison_mode: u32,
+    #[serde(default)]
+    special_judge: Option<String>,
+    #[serde(default)]
+    test_cases: Vec<LemonTestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LemonTestCase {
+    full_score: u32,
+    time_limit: u64,
+    memory_limit: u64,
+    #[serde(default)]
+    input_files: Vec<String>,
+    #[serde(default)]
+    output_files: Vec<String>,
+}
+
+/// 将 Lemon 的一道题导入为题目目录
+fn import_task(task: &LemonTask, data_dir: &Path, day_dir: &Path) -> Result<()> {
+    // 优先使用源文件名，其次是输入文件名，最后才是题目标题
+    let name = task
+        .source_file_name
+        .clone()
+        .or_else(|| {
+            task.input_file_name.as_ref().and_then(|file| {
+                Path::new(file)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| task.problem_title.clone());
+    if name.is_empty() {
+        bail!("无法确定题目名称");
+    }
+
+    let problem_type = match task.task_type {
+        0 => ProblemType::Program,
+        1 => ProblemType::Output,
+        other => bail!("题目 {} 的类型 ({}) 暂不支持导入", name, other),
+    };
+
+    info!("导入题目: {}", name);
+    let problem_dir = new_problem(day_dir, &name)?;
+    let mut problem = load_problem_config(&problem_dir.join(CONFIG_FILE_NAME))?;
+
+    problem.title = task.problem_title.clone();
+    problem.problem_type = problem_type;
+    problem.samples = Vec::new();
+
+    let time_limits: Vec<u64> = task.test_cases.iter().map(|case| case.time_limit).collect();
+    let memory_limits: Vec<u64> = task
+        .test_cases
+        .iter()
+        .map(|case| case.memory_limit)
+        .collect();
+    if time_limits.windows(2).any(|w| w[0] != w[1])
+        || memory_limits.windows(2).any(|w| w[0] != w[1])
+    {
+        warn!("题目 {} 各测试点的时空限制不同，将使用最大值", name);
+    }
+    if let Some(time_limit) = time_limits.iter().max() {
+        problem.time_limit = *time_limit as f64 / 1000.0;
+    }
+    if let Some(memory_limit) = memory_limits.iter().max() {
+        problem.memory_limit = ByteSize::mib(*memory_limit);
+    }
+
+    // 单个文件的测试点放在子任务 0 中求和，捆绑测试的每组各为一个子任务
+    let mut orig_data = Vec::new();
+    let mut subtasks = BTreeMap::new();
+    let mut id = 0;
+    let mut bundle = 0;
+
+    for (index, case) in task.test_cases.iter().enumerate() {
+        if case.input_files.len() != case.output_files.len() {
+            bail!(
+                "题目 {} 的第 {} 组测试点输入输出文件数量不一致",
+                name,
+                index + 1
+            );
+        }
+        let subtask = if case.input_files.len() > 1 {
+            bundle += 1;
+            subtasks.insert(bundle, ScorePolicy::Min);
+            bundle
+        } else {
+            subtasks.insert(0, ScorePolicy::Sum);
+            0
+        };
+
+        for (input, output) in case.input_files.iter().zip(&case.output_files) {
+            id += 1;
+            copy_file(
+                &data_dir.join(input),
+                &problem_dir.join("data").join(format!("{}.in", id)),
+            )?;
+            copy_file(
+                &data_dir.join(output),
+                &problem_dir.join("data").join(format!("{}.ans", id)),
+            )?;
+            orig_data.push(DataItem::Single(SingleDataItem {
+                id,
+                score: case.full_score,
+                subtask,
+                input: Optional::initialized(format!("{}.in", id)),
+                output: Optional::initialized(format!("{}.ans", id)),
+                args: HashMap::new(),
+                manual: None,
+            }));
+        }
+    }
+    if subtasks.is_empty() {
+        subtasks.insert(0, ScorePolicy::Sum);
+    }
+    problem.orig_data = orig_data;
+    problem.orig_subtasks = subtasks;
+
+    // Lemon 的检查器为可执行文件，只能尝试寻找同名源文件
+    if task.comparison_mode == 4 {
+        let source = task
+            .special_judge
+            .as_ref()
+            .map(|path| data_dir.join(path).with_extension("cpp"))
+            .filter(|path| path.exists());
+        match source {
+            Some(source) => {
+                copy_file(
+                    &source,
+                    &problem_dir.join("data").join("chk").join("chk.cpp"),
+                )?;
+                problem.use_chk = Some(true);
+                warn!(
+                    "题目 {} 的检查器已导入，Lemon 与 testlib 的检查器接口不同，请手动适配",
+                    name
+                );
+            }
+            None => warn!(
+                "题目 {} 使用了自定义检查器，但找不到其源文件，请手动放置到 data/chk/chk.cpp",
+                name
+            ),
+        }
+    }
+
+    let updated_content = save_problem_config(&problem)?;
+    fs::write(problem_dir.join(CONFIG_FILE_NAME), updated_content)?;
+
+    info!(
+        "题目 {} 导入完成：{} 个测试点，{} 个子任务",
+        name,
+        problem.orig_data.len(),
+        problem.orig_subtasks.len()
+    );
+
+    Ok(())
+}
+
+pub fn main(args: ImportLemonArgs) -> Result<()> {
+    let content = fs::read_to_string(&args.path)
+        .with_context(|| format!("无法读取 {}", args.path.display()))?;
+    let lemon: LemonContest = serde_json::from_str(&content)
+        .context("无法解析 cdf 文件，目前仅支持 JSON 格式的 cdf 文件（LemonLime）")?;
+
+    let data_dir = args
+        .path
+        .parent()
+        .map(|dir| dir.join("data"))
+        .context("无法获取 cdf 文件所在目录")?;
+
+    let name = match args.name {
+        Some(name) => name,
+        None => args
+            .path
+            .file_stem()
+            .context("无法确定竞赛名称，请使用 --name 指定")?
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    let current_dir = std::env::current_dir()?;
+    if current_dir.join(&name).exists() {
+        bail!("目录 {} 已存在", name);
+    }
+
+    let contest_dir = new_contest(&current_dir, &name)?;
+    let mut contest = load_contest_config(&contest_dir.join(CONFIG_FILE_NAME))?;
+    contest.title = lemon.contest_title.clone();
+    fs::write(
+        contest_dir.join(CONFIG_FILE_NAME),
+        save_contest_config(&contest)?,
+    )?;
+
+    let day_dir = new_day(&contest_dir, &args.day)?;
+    let mut day = load_day_config(&day_dir.join(CONFIG_FILE_NAME))?;
+    day.title = lemon.contest_title.clone();
+    fs::write(day_dir.join(CONFIG_FILE_NAME), save_day_config(&day)?)?;
+
+    for task in &lemon.tasks {
+        import_task(task, &data_dir, &day_dir)?;
+    }
+
+    warn!("Lemon 不保存编译选项，已使用 scaffold 中的默认设置");
+    info!("导入完成，共 {} 道题目", lemon.tasks.len());
+
+    Ok(())
+}
@@ -0,0 +1,592 @@
+use super::{copy_file, open_package, require_folder};
+use crate::config::{SingleDataItem, load_problem_config, save_problem_config};
+use crate::generate::data::spread;
+use crate::generate::{CONFIG_FILE_NAME, new_problem};
+use crate::prelude::*;
+use crate::utils::optional::Optional;
+use bytesize::ByteSize;
+use clap::Args;
+use indexmap::IndexMap;
+use quick_xml::de::from_str;
+use regex::Regex;
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportPolygonArgs {
+    /// Polygon 题目包（zip 文件或解压后的目录）
+    #[arg(required = true)]
+    pub path: PathBuf,
+
+    /// 题目英文名称，默认使用题目包中的 short-name
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonProblem {
+    #[serde(rename = "@short-name", default)]
+    short_name: Option<String>,
+    #[serde(default)]
+    names: Option<Names>,
+    judging: Judging,
+    #[serde(default)]
+    files: Option<Files>,
+    #[serde(default)]
+    assets: Option<Assets>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Names {
+    #[serde(default)]
+    name: Vec<Name>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Name {
+    #[serde(rename = "@language")]
+    language: String,
+    #[serde(rename = "@value")]
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Judging {
+    #[serde(default)]
+    testset: Vec<Testset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Testset {
+    #[serde(rename = "@name")]
+    name: String,
+    time_limit: u64,
+    memory_limit: u64,
+    input_path_pattern: String,
+    answer_path_pattern: String,
+    #[serde(default)]
+    tests: Option<Tests>,
+    #[serde(default)]
+    groups: Option<Groups>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tests {
+    #[serde(default)]
+    test: Vec<Test>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Test {
+    #[serde(rename = "@sample", default)]
+    sample: Option<bool>,
+    #[serde(rename = "@group", default)]
+    group: Option<String>,
+    #[serde(rename = "@points", default)]
+    points: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Groups {
+    #[serde(default)]
+    group: Vec<Group>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Group {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@points", default)]
+    points: Option<f64>,
+    #[serde(rename = "@points-policy", default)]
+    points_policy: Option<String>,
+    #[serde(default)]
+    dependencies: Option<Dependencies>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependencies {
+    #[serde(default)]
+    dependency: Vec<Dependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependency {
+    #[serde(rename = "@group")]
+    group: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Files {
+    #[serde(default)]
+    resources: Option<Resources>,
+    #[serde(default)]
+    executables: Option<Executables>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resources {
+    #[serde(default)]
+    file: Vec<Source>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Executables {
+    #[serde(default)]
+    executable: Vec<WithSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Source {
+    #[serde(rename = "@path")]
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WithSource {
+    source: Source,
+}
+
+#[derive(Debug, Deserialize)]
+struct Assets {
+    #[serde(default)]
+    checker: Option<Checker>,
+    #[serde(default)]
+    validators: Option<Validators>,
+    #[serde(default)]
+    solutions: Option<Solutions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Checker {
+    #[serde(rename = "@name", default)]
+    name: Option<String>,
+    source: Source,
+}
+
+#[derive(Debug, Deserialize)]
+struct Validators {
+    #[serde(default)]
+    validator: Vec<WithSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Solutions {
+    #[serde(default)]
+    solution: Vec<Solution>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Solution {
+    #[serde(rename = "@tag")]
+    tag: String,
+    source: Source,
+}
+
+/// 将 Polygon 的路径模板（如 `tests/%02d`）展开为具体路径
+fn apply_pattern(pattern: &str, index: usize) -> String {
+    let regex = Regex::new(r"%(0?)(\d*)d").unwrap();
+    regex
+        .replace(pattern, |caps: &regex::Captures| {
+            let width: usize = caps[2].parse().unwrap_or(0);
+            if &caps[1] == "0" {
+                format!("{:0width$}", index, width = width)
+            } else {
+                format!("{:width$}", index, width = width)
+            }
+        })
+        .to_string()
+}
+
+/// 将 Polygon 的解法标签映射为期望得分条件，返回 `None` 表示不导入
+fn expected_of_tag(tag: &str) -> Option<ExpectedScore> {
+    match tag {
+        "main" | "accepted" => Some(ExpectedScore::Single("== 100".to_string())),
+        "do-not-run" => None,
+        "time-limit-exceeded-or-accepted" => Some(ExpectedScore::Multiple(vec![])),
+        _ => Some(ExpectedScore::Single("< 100".to_string())),
+    }
+}
+
+/// 读取题面片段并转换为 Markdown 题面
+fn convert_statement(package: &Path, problem: &mut ProblemConfig) -> Result<Option<String>> {
+    let sections_dir = package.join("statement-sections");
+    if !sections_dir.is_dir() {
+        return Ok(None);
+    }
+
+    let mut languages: Vec<PathBuf> = fs::read_dir(&sections_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    languages.sort_by_key(|path| match path.file_name().and_then(|n| n.to_str()) {
+        Some("chinese") => 0,
+        Some("english") => 1,
+        _ => 2,
+    });
+    let Some(dir) = languages.first() else {
+        return Ok(None);
+    };
+
+    // Codeforces 使用 $$$ 作为行内公式分隔符
+    let read = |name: &str| -> String {
+        fs::read_to_string(dir.join(name))
+            .map(|content| content.replace("$$$$$$", "$$").replace("$$$", "$"))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    let mut statement = format!(
+        "## 题目描述\n\n{}\n\n## 输入格式\n\n{}\n\n## 输出格式\n\n{}\n\n",
+        read("legend.tex"),
+        read("input.tex"),
+        read("output.tex")
+    );
+    for sample in &problem.samples {
+        statement.push_str(&format!("{{{{ sample.text({}) }}}}\n\n", sample.id));
+    }
+    let notes = read("notes.tex");
+    if !notes.is_empty() {
+        statement.push_str(&format!("## 提示\n\n{}\n\n", notes));
+    }
+    let scoring = read("scoring.tex");
+    if !scoring.is_empty() {
+        statement.push_str(&format!("## 数据范围\n\n{}\n", scoring));
+    }
+
+    if problem.title.is_empty()
+        && let Ok(name) = fs::read_to_string(dir.join("name.tex"))
+    {
+        problem.title = name.trim().to_string();
+    }
+
+    Ok(Some(statement))
+}
+
+fn import(package: &Path, day_dir: &Path, name: Option<String>) -> Result<()> {
+    let xml = fs::read_to_string(package.join("problem.xml"))
+        .context("题目包中缺少 problem.xml，可能不是 Polygon 题目包")?;
+    let polygon: PolygonProblem = from_str(&xml).context("无法解析 problem.xml")?;
+
+    let name = name
+        .or(polygon.short_name.clone())
+        .context("无法确定题目名称，请使用 --name 指定")?;
+    if day_dir.join(&name).exists() {
+        bail!("题目目录 {} 已存在", name);
+    }
+
+    let testset = polygon
+        .judging
+        .testset
+        .iter()
+        .find(|testset| testset.name == "tests")
+        .or(polygon.judging.testset.first())
+        .context("problem.xml 中没有测试集")?;
+
+    info!("导入 Polygon 题目: {}", name);
+    let problem_dir = new_problem(day_dir, &name)?;
+    let mut problem = load_problem_config(&problem_dir.join(CONFIG_FILE_NAME))?;
+
+    problem.title = polygon
+        .names
+        .as_ref()
+        .and_then(|names| {
+            names
+                .name
+                .iter()
+                .find(|name| name.language == "chinese")
+                .or(names.name.first())
+        })
+        .map(|name| name.value.clone())
+        .unwrap_or_default();
+    problem.time_limit = testset.time_limit as f64 / 1000.0;
+    problem.memory_limit = ByteSize::b(testset.memory_limit);
+
+    // 测试数据与样例
+    let tests: &[Test] = testset
+        .tests
+        .as_ref()
+        .map(|tests| tests.test.as_slice())
+        .unwrap_or_default();
+    let data_dir = problem_dir.join("data");
+    let sample_dir = problem_dir.join("sample");
+    fs::create_dir_all(&data_dir)?;
+    fs::create_dir_all(&sample_dir)?;
+
+    let mut samples = Vec::new();
+    for (index, test) in tests.iter().enumerate() {
+        let id = index + 1;
+        let input = package.join(apply_pattern(&testset.input_path_pattern, id));
+        let answer = package.join(apply_pattern(&testset.answer_path_pattern, id));
+        if !input.exists() {
+            warn!(
+                "测试点 #{} 的输入文件不存在，请使用完整的题目包或自行生成",
+                id
+            );
+            continue;
+        }
+        copy_file(&input, &data_dir.join(format!("{}.in", id)))?;
+        if answer.exists() {
+            copy_file(&answer, &data_dir.join(format!("{}.ans", id)))?;
+        } else {
+            warn!("测试点 #{} 的答案文件不存在，请使用 dmk 生成", id);
+        }
+
+        if test.sample.unwrap_or(false) && answer.exists() {
+            let sample_id = samples.len() as u32 + 1;
+            copy_file(&input, &sample_dir.join(format!("{}.in", sample_id)))?;
+            copy_file(&answer, &sample_dir.join(format!("{}.ans", sample_id)))?;
+            samples.push(SampleItem {
+                id: sample_id,
+                input: Optional::initialized(format!("{}.in", sample_id)),
+                output: Optional::initialized(format!("{}.ans", sample_id)),
+                args: HashMap::new(),
+                manual: None,
+            });
+        }
+    }
+    problem.samples = samples;
+
+    // 测试点分组作为子任务，组名不全为数字时按出现顺序编号
+    let groups: &[Group] = testset
+        .groups
+        .as_ref()
+        .map(|groups| groups.group.as_slice())
+        .unwrap_or_default();
+    let mut group_names: Vec<String> = groups.iter().map(|group| group.name.clone()).collect();
+    for test in tests {
+        if let Some(group) = &test.group
+            && !group_names.contains(group)
+        {
+            group_names.push(group.clone());
+        }
+    }
+    let numeric = group_names.iter().all(|name| name.parse::<u32>().is_ok());
+    let subtask_of = |group: &Option<String>| -> u32 {
+        match group {
+            None => 0,
+            Some(group) if numeric => group.parse().unwrap(),
+            Some(group) => group_names.iter().position(|name| name == group).unwrap() as u32 + 1,
+        }
+    };
+
+    let mut subtasks: BTreeMap<u32, ScorePolicy> = BTreeMap::new();
+    let mut group_score: HashMap<u32, u32> = HashMap::new();
+    for group in groups {
+        let id = subtask_of(&Some(group.name.clone()));
+        let policy = match group.points_policy.as_deref() {
+            Some("each-test") => ScorePolicy::Sum,
+            _ => ScorePolicy::Min,
+        };
+        subtasks.insert(id, policy);
+        if let Some(points) = group.points {
+            group_score.insert(id, points.round() as u32);
+        }
+        if let Some(dependencies) = &group.dependencies
+            && !dependencies.dependency.is_empty()
+        {
+            warn!(
+                "子任务 {} 依赖于 {}，tuack-ng 不支持子任务依赖，已忽略",
+                group.name,
+                dependencies
+                    .dependency
+                    .iter()
+                    .map(|dep| dep.group.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    for test in tests {
+        subtasks
+            .entry(subtask_of(&test.group))
+            .or_insert(ScorePolicy::Sum);
+    }
+    if subtasks.is_empty() {
+        subtasks.insert(0, ScorePolicy::Sum);
+    }
+
+    // 没有任何分值信息时（如 ICPC 赛制题目）平均分配 100 分
+    let has_points = tests.iter().any(|test| test.points.is_some()) || !group_score.is_empty();
+    let even_scores = spread(100, tests.len());
+
+    problem.orig_data = tests
+        .iter()
+        .enumerate()
+        .map(|(index, test)| {
+            let id = index as u32 + 1;
+            let subtask = subtask_of(&test.group);
+            let score = if !has_points {
+                even_scores[index]
+            } else {
+                match subtasks[&subtask] {
+                    ScorePolicy::Sum => test.points.unwrap_or(0.0).round() as u32,
+                    ScorePolicy::Min | ScorePolicy::Max => {
+                        group_score.get(&subtask).copied().unwrap_or_else(|| {
+                            tests
+                                .iter()
+                                .filter(|other| subtask_of(&other.group) == subtask)
+                                .map(|other| other.points.unwrap_or(0.0))
+                                .sum::<f64>()
+                                .round() as u32
+                        })
+                    }
+                }
+            };
+            DataItem::Single(SingleDataItem {
+                id,
+                score,
+                subtask,
+                input: Optional::initialized(format!("{}.in", id)),
+                output: Optional::initialized(format!("{}.ans", id)),
+                args: HashMap::new(),
+                manual: None,
+            })
+        })
+        .collect();
+    problem.orig_subtasks = subtasks;
+
+    // 检查器、校验器与生成器
+    let resources: Vec<PathBuf> = polygon
+        .files
+        .as_ref()
+        .and_then(|files| files.resources.as_ref())
+        .map(|resources| {
+            resources
+                .file
+                .iter()
+                .map(|file| package.join(&file.path))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "h"))
+                .collect()
+        })
+        .unwrap_or_default();
+    let copy_with_resources = |source: &Path, dir: &Path, name: &str| -> Result<()> {
+        copy_file(source, &dir.join(name))?;
+        for resource in &resources {
+            if resource.exists() {
+                copy_file(resource, &dir.join(resource.file_name().unwrap()))?;
+            }
+        }
+        Ok(())
+    };
+
+    let mut used_sources: Vec<String> = Vec::new();
+    if let Some(assets) = &polygon.assets {
+        if let Some(checker) = &assets.checker {
+            used_sources.push(checker.source.path.clone());
+            let source = package.join(&checker.source.path);
+            if checker
+                .name
+                .as_deref()
+                .is_some_and(|name| name.starts_with("std::"))
+            {
+                info!(
+                    "题目使用 Polygon 标准检查器 {}，将使用默认检查器",
+                    checker.name.as_deref().unwrap()
+                );
+            } else if source.exists() {
+                copy_with_resources(&source, &data_dir.join("chk"), "chk.cpp")?;
+                problem.use_chk = Some(true);
+                info!("已导入自定义检查器");
+            } else {
+                warn!("检查器源文件 {} 不存在", checker.source.path);
+            }
+        }
+
+        if let Some(validators) = &assets.validators {
+            for validator in &validators.validator {
+                used_sources.push(validator.source.path.clone());
+                let source = package.join(&validator.source.path);
+                if source.exists() {
+                    copy_with_resources(
+                        &source,
+                        &problem_dir.join("val"),
+                        &source.file_name().unwrap().to_string_lossy(),
+                    )?;
+                }
+            }
+        }
+
+        if let Some(solutions) = &assets.solutions {
+            let mut tests = IndexMap::<String, TestCase>::new();
+            // 主解法放在最前，以便 dmk 将其识别为标程
+            let mut solutions: Vec<&Solution> = solutions.solution.iter().collect();
+            solutions.sort_by_key(|solution| solution.tag != "main");
+
+            for solution in solutions {
+                let Some(expected) = expected_of_tag(&solution.tag) else {
+                    continue;
+                };
+                let source = package.join(&solution.source.path);
+                if !source.exists() {
+                    warn!("解法 {} 不存在", solution.source.path);
+                    continue;
+                }
+                let path = format!("tests/{}", source.file_name().unwrap().to_string_lossy());
+                copy_file(&source, &problem_dir.join(&path))?;
+                let name = if solution.tag == "main" {
+                    "std".to_string()
+                } else {
+                    path.clone()
+                };
+                tests.insert(name, TestCase { expected, path });
+            }
+            problem.tests = tests;
+        }
+    }
+
+    if let Some(executables) = polygon
+        .files
+        .as_ref()
+        .and_then(|files| files.executables.as_ref())
+    {
+        let generators: Vec<&WithSource> = executables
+            .executable
+            .iter()
+            .filter(|exe| !used_sources.contains(&exe.source.path))
+            .collect();
+        for generator in &generators {
+            let source = package.join(&generator.source.path);
+            if source.exists() {
+                copy_with_resources(
+                    &source,
+                    &problem_dir.join("gen"),
+                    &source.file_name().unwrap().to_string_lossy(),
+                )?;
+            }
+        }
+        if !generators.is_empty() {
+            warn!("Polygon 生成器的调用方式与 dmk 不同，已复制到 gen 目录，需要手动适配");
+        }
+    }
+
+    if let Some(statement) = convert_statement(package, &mut problem)? {
+        fs::write(problem_dir.join("statement.md"), statement)?;
+    }
+
+    let updated_content = save_problem_config(&problem)?;
+    fs::write(problem_dir.join(CONFIG_FILE_NAME), updated_content)?;
+
+    info!(
+        "导入完成：{} 个测试点，{} 个样例，{} 个子任务",
+        problem.orig_data.len(),
+        problem.samples.len(),
+        problem.orig_subtasks.len()
+    );
+
+    Ok(())
+}
+
+pub fn main(args: ImportPolygonArgs) -> Result<()> {
+    let day_dir = require_folder("day")?;
+    let (package, tmp_dir) = open_package(&args.path)?;
+
+    let result = import(&package, &day_dir, args.name);
+
+    if let Some(tmp_dir) = tmp_dir {
+        let _ = fs::remove_dir_all(tmp_dir);
+    }
+
+    result
+}
@@ -0,0 +1,433 @@
+use crate::prelude::*;
+use crate::rank::{StandingRow, Standings, Tiebreak, export};
+use crate::test::{
+    ProblemStatus, TestCaseStatus, case_score, compile, compile_chk, create_or_clear_dir,
+    run_test_case, subtask_score, validate_output,
+};
+use clap::Args;
+use csv::Writer;
+use indicatif::ProgressBar;
+
+#[derive(Args, Debug)]
+#[command(version)]
+pub struct JudgeArgs {
+    /// 选手目录，其中每个子目录为一名选手，按 <选手>/<题目>/<题目>.<语言> 存放提交
+    #[arg(required = true)]
+    pub players: PathBuf,
+
+    /// 结果输出目录，默认为竞赛日目录下的 judge
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// 忽略已有的评测结果，重新评测所有选手
+    #[arg(long)]
+    pub restart: bool,
+}
+
+/// 一个测试点的评测记录
+#[derive(Debug, Serialize, Deserialize)]
+struct CaseRecord {
+    id: u32,
+    status: String,
+    score: u32,
+    max_score: u32,
+    time: String,
+    memory: String,
+}
+
+/// 一道题目的评测记录
+#[derive(Debug, Serialize, Deserialize)]
+struct ProblemRecord {
+    source: Option<String>,
+    status: String,
+    score: u32,
+    /// 各测试点的总用时（毫秒）
+    #[serde(default)]
+    time_ms: u64,
+    cases: Vec<CaseRecord>,
+}
+
+/// 一名选手的评测记录，按题目名称索引
+type PlayerRecord = BTreeMap<String, ProblemRecord>;
+
+/// 查找选手对某道题目的提交，存在多个语言的提交时取第一个
+fn find_source(day: &ContestDayConfig, prob: &ProblemConfig, player_dir: &Path) -> Option<PathBuf> {
+    let mut langs: Vec<&String> = day.compile.keys().collect();
+    langs.sort();
+    let sources: Vec<PathBuf> = langs
+        .iter()
+        .map(|lang| {
+            player_dir
+                .join(&prob.name)
+                .join(format!("{}.{}", prob.name, lang))
+        })
+        .filter(|path| path.is_file())
+        .collect();
+    if sources.len() > 1 {
+        warn!(
+            "{} 下有多个提交，只评测 {}",
+            player_dir.join(&prob.name).display(),
+            sources[0].display()
+        );
+    }
+    sources.into_iter().next()
+}
+
+/// 编译并评测一份提交
+fn judge_problem(
+    day: &ContestDayConfig,
+    prob: &ProblemConfig,
+    source: &Path,
+    tmp_dir: &PathBuf,
+    chk: Option<&PathBuf>,
+) -> Result<ProblemRecord> {
+    create_or_clear_dir(tmp_dir)?;
+    let src_path = tmp_dir.join(source.file_name().unwrap());
+    fs::copy(source, &src_path)?;
+
+    let mut problem_status = ProblemStatus::Compiling;
+    compile(day, prob, &mut problem_status, tmp_dir, &src_path)?;
+    fs::remove_file(&src_path)?;
+
+    let source = Some(source.to_string_lossy().to_string());
+    if problem_status != ProblemStatus::Compiled {
+        return Ok(ProblemRecord {
+            source,
+            status: format!("{:?}", TestCaseStatus::CE),
+            score: 0,
+            time_ms: 0,
+            cases: Vec::new(),
+        });
+    }
+
+    let program_path = tmp_dir.join(&prob.name);
+    let file_io = prob.file_io.unwrap_or(true);
+    let mut cases = Vec::new();
+    let mut time_ms = 0;
+    let mut subtask_scores: HashMap<u32, Vec<u32>> =
+        prob.subtasks.keys().map(|id| (*id, Vec::new())).collect();
+
+    for case in &prob.data {
+        info!("运行测试点: {}", case.id);
+
+        // 清除上一个测试点的输出，避免未输出时沿用旧的结果
+        for ext in ["out", "stdout"] {
+            let output = tmp_dir.join(format!("{}.{}", prob.name, ext));
+            if output.exists() {
+                fs::remove_file(output)?;
+            }
+        }
+
+        let run_result = run_test_case(
+            &src_path,
+            &program_path,
+            &prob.name,
+            &prob.path.join("data").join(&case.input),
+            (prob.time_limit * 1000.0) as u128,
+            prob.memory_limit.as_u64(),
+            file_io,
+        )?;
+
+        let case_status = match run_result.0 {
+            TestCaseStatus::Running => validate_output(
+                tmp_dir,
+                &prob.name,
+                &prob.path.join("data").join(&case.output),
+                file_io,
+                chk.cloned(),
+            )?,
+            status => status,
+        };
+
+        if let Some(duration) = run_result.1 {
+            time_ms += duration.as_millis() as u64;
+        }
+
+        let earned_score = case_score(case_status, case.score);
+        subtask_scores
+            .get_mut(&case.subtask)
+            .context("不存在指定的 Subtask")?
+            .push(earned_score);
+
+        cases.push(CaseRecord {
+            id: case.id,
+            status: format!("{:?}", case_status),
+            score: earned_score,
+            max_score: case.score,
+            time: match run_result.1 {
+                Some(duration) => format!("{:?}", duration),
+                None => "N/A".to_string(),
+            },
+            memory: match run_result.2 {
+                Some(memory) => format!("{}", memory),
+                None => "N/A".to_string(),
+            },
+        });
+    }
+
+    let score = prob
+        .subtasks
+        .iter()
+        .map(|(id, subtask)| subtask_score(subtask.policy, &subtask_scores[id]))
+        .sum();
+
+    Ok(ProblemRecord {
+        source,
+        status: "OK".to_string(),
+        score,
+        time_ms,
+        cases,
+    })
+}
+
+/// 保存选手的评测记录，先写入临时文件再重命名，避免中断时留下不完整的文件
+fn save_record(path: &Path, record: &PlayerRecord) -> Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string_pretty(record)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// 写入选手的逐测试点评测结果
+fn write_detail(path: &Path, problems: &[&ProblemConfig], record: &PlayerRecord) -> Result<()> {
+    let mut wtr = Writer::from_path(path)?;
+
+    wtr.write_record(["题目", "测试点ID", "状态", "得分", "最高分", "时间", "空间"])?;
+
+    for prob in problems {
+        let Some(result) = record.get(&prob.name) else {
+            continue;
+        };
+        for case in &result.cases {
+            wtr.write_record(&[
+                prob.name.clone(),
+                case.id.to_string(),
+                case.status.clone(),
+                case.score.to_string(),
+                case.max_score.to_string(),
+                case.time.clone(),
+                case.memory.clone(),
+            ])?;
+        }
+        wtr.write_record(&[
+            prob.name.clone(),
+            "".to_string(),
+            result.status.clone(),
+            result.score.to_string(),
+            prob.subtasks
+                .values()
+                .map(|task| task.max_score)
+                .sum::<u32>()
+                .to_string(),
+            "".to_string(),
+            "".to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// 写入所有选手的成绩 scores.csv，可作为 rank 命令的输入
+fn write_scores(
+    path: &Path,
+    problems: &[&ProblemConfig],
+    records: &[(String, PlayerRecord)],
+) -> Result<()> {
+    let mut wtr = Writer::from_path(path)?;
+
+    wtr.write_record(["id", "problem", "score", "time"])?;
+
+    for (player, record) in records {
+        for prob in problems {
+            if let Some(result) = record.get(&prob.name) {
+                wtr.write_record(&[
+                    player.clone(),
+                    prob.name.clone(),
+                    result.score.to_string(),
+                    result.time_ms.to_string(),
+                ])?;
+            }
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// 生成排名表，总分相同的选手名次相同
+fn standings(
+    day: &ContestDayConfig,
+    problems: &[&ProblemConfig],
+    records: &[(String, PlayerRecord)],
+) -> Standings {
+    let rows = records
+        .iter()
+        .map(|(player, record)| {
+            let scores: Vec<Option<u32>> = problems
+                .iter()
+                .map(|prob| record.get(&prob.name).map(|result| result.score))
+                .collect();
+            StandingRow {
+                rank: 0,
+                id: player.clone(),
+                name: player.clone(),
+                total: scores.iter().flatten().sum(),
+                scores,
+                time_ms: Some(record.values().map(|result| result.time_ms).sum()),
+            }
+        })
+        .collect();
+
+    let title = if day.title.is_empty() {
+        day.name.clone()
+    } else {
+        day.title.clone()
+    };
+    Standings::new(
+        title,
+        problems.iter().map(|prob| prob.name.clone()).collect(),
+        rows,
+        Tiebreak::None,
+    )
+}
+
+pub fn main(args: JudgeArgs) -> Result<()> {
+    let (config, current_location) = get_context().config.as_ref().context("找不到配置文件")?;
+
+    let (day_name, target_problem) = match current_location {
+        CurrentLocation::Day(day_name) => (day_name, None),
+        CurrentLocation::Problem(day_name, problem_name) => (day_name, Some(problem_name)),
+        _ => bail!("此命令必须在竞赛日或题目下执行"),
+    };
+    let day = config
+        .subconfig
+        .get(day_name)
+        .with_context(|| format!("未找到天配置: {}", day_name))?;
+
+    let problems: Vec<&ProblemConfig> = day
+        .subconfig
+        .values()
+        .filter(|prob| target_problem.is_none_or(|name| *name == prob.name))
+        .filter(|prob| {
+            let supported = matches!(prob.problem_type, ProblemType::Program);
+            if !supported {
+                warn!("暂不支持评测非传统题，跳过题目 {}", prob.name);
+            }
+            supported
+        })
+        .collect();
+
+    if !args.players.is_dir() {
+        bail!("选手目录不存在: {}", args.players.display());
+    }
+
+    let output_dir = args
+        .output
+        .clone()
+        .unwrap_or_else(|| day.path.join("judge"));
+    let results_dir = output_dir.join("results");
+    let details_dir = output_dir.join("details");
+    let tmp_dir = output_dir.join("tmp");
+    if args.restart && results_dir.exists() {
+        info!("清除已有的评测结果");
+        fs::remove_dir_all(&results_dir)?;
+    }
+    fs::create_dir_all(&results_dir)?;
+    fs::create_dir_all(&details_dir)?;
+
+    let mut checkers = HashMap::new();
+    for prob in &problems {
+        if prob.use_chk.unwrap_or(false) {
+            info!("编译题目 {} 的 chk", prob.name);
+            checkers.insert(prob.name.clone(), compile_chk(prob)?);
+        }
+    }
+
+    let mut players = Vec::new();
+    for entry in fs::read_dir(&args.players)? {
+        let entry = entry?;
+        if entry.path().is_dir() {
+            players.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    players.sort_by(|a, b| natord::compare(a, b));
+    info!("共 {} 名选手", players.len());
+
+    let player_pb = get_context()
+        .multiprogress
+        .add(ProgressBar::new(players.len() as u64));
+    player_pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("  [{bar:40.yellow/blue}] {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+
+    let mut records = Vec::new();
+    for player in &players {
+        player_pb.set_message(format!("评测选手: {}", player));
+
+        let record_path = results_dir.join(format!("{}.json", player));
+        let mut record: PlayerRecord = if record_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&record_path)?)
+                .with_context(|| format!("无法读取评测记录 {}", record_path.display()))?
+        } else {
+            PlayerRecord::new()
+        };
+
+        for prob in &problems {
+            if record.contains_key(&prob.name) {
+                debug!("选手 {} 的题目 {} 已评测，跳过", player, prob.name);
+                continue;
+            }
+
+            let result = match find_source(day, prob, &args.players.join(player)) {
+                Some(source) => {
+                    info!("评测选手 {} 的题目 {}", player, prob.name);
+                    judge_problem(day, prob, &source, &tmp_dir, checkers.get(&prob.name))?
+                }
+                None => ProblemRecord {
+                    source: None,
+                    status: "未提交".to_string(),
+                    score: 0,
+                    time_ms: 0,
+                    cases: Vec::new(),
+                },
+            };
+            info!(
+                "选手 {} 的题目 {} 得分: {}",
+                player, prob.name, result.score
+            );
+
+            // 每评测完一道题目就保存，中断后可以继续评测
+            record.insert(prob.name.clone(), result);
+            save_record(&record_path, &record)?;
+        }
+
+        write_detail(
+            &details_dir.join(format!("{}.csv", player)),
+            &problems,
+            &record,
+        )?;
+        records.push((player.clone(), record));
+        player_pb.inc(1);
+    }
+
+    player_pb.finish_and_clear();
+
+    if tmp_dir.exists() {
+        fs::remove_dir_all(&tmp_dir)?;
+    }
+
+    write_scores(&output_dir.join("scores.csv"), &problems, &records)?;
+    export::write_all(
+        &standings(day, &problems, &records),
+        &output_dir,
+        "ranking",
+        None,
+    )?;
+    info!("评测完成，结果已保存到 {}", output_dir.display());
+
+    Ok(())
+}
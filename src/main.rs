@@ -2,8 +2,12 @@ use crate::conf::ConfArgs;
 use crate::dmk::DmkArgs;
 use crate::dump::DumpArgs;
 use crate::generate::GenArgs;
+use crate::judge::JudgeArgs;
+use crate::migrate::MigrateArgs;
 use crate::prelude::*;
+use crate::rank::RankArgs;
 use crate::ren::RenArgs;
+use crate::shrink::ShrinkArgs;
 use crate::test::TestArgs;
 use clap::ArgAction;
 use clap::{Parser, Subcommand};
@@ -16,8 +20,12 @@ mod dmk;
 mod dump;
 mod generate;
 mod init;
+mod judge;
+mod migrate;
 mod prelude;
+mod rank;
 mod ren;
+mod shrink;
 mod test;
 mod utils;
 
@@ -47,6 +55,14 @@ enum Commands {
     Dmk(DmkArgs),
     /// 导出到评测系统
     Dump(DumpArgs),
+    /// 评测选手提交的程序
+    Judge(JudgeArgs),
+    /// 根据评测结果生成排名
+    Rank(RankArgs),
+    /// 缩小使错误解法出错的输入
+    Shrink(ShrinkArgs),
+    /// 将 tuack 工程迁移到 tuack-ng 格式
+    Migrate(MigrateArgs),
 }
 
 fn tuack_ng(cli: Cli) -> Result<()> {
@@ -66,6 +82,10 @@ fn tuack_ng(cli: Cli) -> Result<()> {
         Commands::Conf(args) => conf::main(args),
         Commands::Dmk(args) => dmk::main(args),
         Commands::Dump(args) => dump::main(args),
+        Commands::Judge(args) => judge::main(args),
+        Commands::Rank(args) => rank::main(args),
+        Commands::Shrink(args) => shrink::main(args),
+        Commands::Migrate(args) => migrate::main(args),
     }
 }
 
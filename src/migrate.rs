@@ -0,0 +1,459 @@
+use crate::config::{CONFIG_FILE_NAME, save_contest_config, save_day_config, save_problem_config};
+use crate::prelude::*;
+use bytesize::ByteSize;
+use clap::Args;
+use serde_json::{Map, Value, json};
+
+/// tuack 配置文件的可能名称，按 tuack 的读取顺序排列
+const LEGACY_CONFIG_NAMES: [&str; 2] = ["conf.json", "prob.json"];
+
+#[derive(Args, Debug)]
+#[command(version)]
+pub struct MigrateArgs {
+    /// tuack 工程根目录
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+}
+
+/// 读取 tuack 配置文件，返回配置内容与文件路径；已是 tuack-ng 格式时返回 `None`
+fn read_legacy_config(dir: &Path) -> Result<Option<(Map<String, Value>, PathBuf)>> {
+    for name in LEGACY_CONFIG_NAMES {
+        let path = dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(&fs::read_to_string(&path)?)
+            .with_context(|| format!("无法解析 {}", path.display()))?;
+        let Value::Object(conf) = value else {
+            bail!("{} 不是合法的配置文件", path.display());
+        };
+        if conf.get("version").and_then(|v| v.as_u64()).unwrap_or(0) >= 3 {
+            return Ok(None);
+        }
+        return Ok(Some((conf, path)));
+    }
+
+    if dir.join("conf.yaml").exists() {
+        bail!("暂不支持迁移 YAML 格式的配置文件: {}", dir.display());
+    }
+    bail!("{} 下找不到配置文件", dir.display())
+}
+
+/// 备份旧配置文件并写入新配置文件
+fn write_config(dir: &Path, legacy_path: &Path, content: String) -> Result<()> {
+    let backup = legacy_path.with_extension("json.bak");
+    fs::rename(legacy_path, &backup)?;
+    fs::write(dir.join(CONFIG_FILE_NAME), content)?;
+    info!(
+        "已迁移 {}，旧配置备份为 {}",
+        dir.display(),
+        backup.file_name().unwrap().to_string_lossy()
+    );
+    Ok(())
+}
+
+/// tuack 的标题为多语言字典，优先取中文
+fn localized(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Object(map)) => map
+            .get("zh-cn")
+            .or_else(|| map.values().next())
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+fn subdirs(conf: &Map<String, Value>) -> Vec<String> {
+    conf.get("subdir")
+        .and_then(|v| v.as_array())
+        .map(|dirs| {
+            dirs.iter()
+                .filter_map(|dir| dir.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn dir_name(dir: &Path) -> String {
+    dunce::canonicalize(dir)
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default()
+}
+
+/// 将 `1926-08-17 00:00:00+0800` 形式的时间转换为 tuack-ng 的时间数组
+fn convert_time(value: Option<&Value>) -> [u32; 6] {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|text| {
+            chrono::NaiveDateTime::parse_from_str(text.get(..19)?, "%Y-%m-%d %H:%M:%S").ok()
+        })
+        .map(|time| {
+            use chrono::{Datelike, Timelike};
+            [
+                time.year() as u32,
+                time.month(),
+                time.day(),
+                time.hour(),
+                time.minute(),
+                time.second(),
+            ]
+        })
+        .unwrap_or([1970, 1, 1, 0, 0, 0])
+}
+
+/// tuack 的一组数据：测试点名称列表、分值与参数
+type LegacyDatum = (Vec<String>, Option<f64>, Map<String, Value>);
+
+/// 读取 tuack 的 data/samples 字段
+///
+/// 同时兼容早期版本的 `test cases`/`sample count` 整数字段。
+fn legacy_data(conf: &Map<String, Value>, key: &str, count_key: &str) -> Vec<LegacyDatum> {
+    let mut result = Vec::new();
+
+    for datum in conf
+        .get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let mut cases = Vec::new();
+        for case in datum
+            .get("cases")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let case = match case {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            if case.starts_with(':') {
+                warn!("测试点 {} 引用了上级目录的数据，不支持迁移，已跳过", case);
+                continue;
+            }
+            cases.push(case);
+        }
+        result.push((
+            cases,
+            datum.get("score").and_then(|v| v.as_f64()),
+            datum
+                .get("args")
+                .and_then(|v| v.as_object())
+                .cloned()
+                .unwrap_or_default(),
+        ));
+    }
+
+    if result.is_empty()
+        && let Some(count) = conf.get(count_key).and_then(|v| v.as_u64())
+    {
+        result.push((
+            (1..=count).map(|i| i.to_string()).collect(),
+            None,
+            Map::new(),
+        ));
+    }
+
+    result
+}
+
+/// 将 tuack 的期望得分转换为 tuack-ng 的格式
+fn convert_expected(value: Option<&Value>) -> Value {
+    match value {
+        Some(Value::String(text)) => json!(text),
+        Some(Value::Array(items)) => json!(items),
+        // 早期版本使用 {"<": 100} 形式
+        Some(Value::Object(map)) => {
+            let conds: Vec<String> = map
+                .iter()
+                .map(|(op, value)| format!("{} {}", op, value))
+                .collect();
+            if conds.len() == 1 {
+                json!(conds[0])
+            } else {
+                json!(conds)
+            }
+        }
+        _ => json!([]),
+    }
+}
+
+fn migrate_problem(dir: &Path, conf: &Map<String, Value>) -> Result<Value> {
+    let name = conf
+        .get("name")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty() && *name != ".")
+        .map(str::to_string)
+        .unwrap_or_else(|| dir_name(dir));
+
+    // 数据：全部未设置分值时每个测试点同分，否则每组作为一个子任务
+    let groups = legacy_data(conf, "data", "test cases");
+    let packed = groups.iter().any(|(_, score, _)| score.is_some());
+    let scored: f64 = groups.iter().filter_map(|(_, score, _)| *score).sum();
+    let unscored = groups
+        .iter()
+        .filter(|(_, score, _)| score.is_none())
+        .count();
+    let total_cases: usize = groups.iter().map(|(cases, _, _)| cases.len()).sum();
+
+    let mut data = Vec::new();
+    let mut subtasks = Map::new();
+    let mut id = 0;
+    for (index, (cases, score, args)) in groups.iter().enumerate() {
+        let subtask = if packed { index + 1 } else { 0 };
+        let item_score = if packed {
+            score.unwrap_or((100.0 - scored) / unscored.max(1) as f64)
+        } else {
+            100.0 / total_cases.max(1) as f64
+        };
+        subtasks.insert(
+            subtask.to_string(),
+            json!(if packed { "min" } else { "sum" }),
+        );
+        for case in cases {
+            id += 1;
+            if !dir.join("data").join(format!("{}.in", case)).exists() {
+                warn!("题目 {} 的测试点 {} 不存在输入文件", name, case);
+            }
+            let mut item = json!({
+                "id": id,
+                "score": item_score.round() as u32,
+                "subtask": subtask,
+                "input": format!("{}.in", case),
+                "output": format!("{}.ans", case),
+            });
+            if !args.is_empty() {
+                item["args"] = Value::Object(args.clone());
+            }
+            data.push(item);
+        }
+    }
+    if subtasks.is_empty() {
+        subtasks.insert("0".to_string(), json!("sum"));
+    }
+
+    // 样例：tuack 放在 down 目录，tuack-ng 放在 sample 目录
+    let mut samples = Vec::new();
+    for case in legacy_data(conf, "samples", "sample count")
+        .into_iter()
+        .flat_map(|(cases, _, _)| cases)
+    {
+        for ext in ["in", "ans"] {
+            let from = dir.join("down").join(format!("{}.{}", case, ext));
+            let to = dir.join("sample").join(format!("{}.{}", case, ext));
+            if from.exists() {
+                fs::create_dir_all(dir.join("sample"))?;
+                fs::copy(&from, &to)?;
+            } else {
+                warn!("题目 {} 的样例 {} 不存在", name, from.display());
+            }
+        }
+        samples.push(json!({
+            "id": samples.len() + 1,
+            "input": format!("{}.in", case),
+            "output": format!("{}.ans", case),
+        }));
+    }
+
+    if conf
+        .get("pre")
+        .and_then(|v| v.as_array())
+        .is_some_and(|pre| !pre.is_empty())
+    {
+        warn!(
+            "题目 {} 的预测试点（pre）暂不支持迁移，已保留 pre 目录",
+            name
+        );
+    }
+
+    // 题解：users.<用户>.<算法> 为路径或 {path, expected}
+    let mut tests = Map::new();
+    for (user, algos) in conf
+        .get("users")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flatten()
+    {
+        for (algo, info) in algos.as_object().into_iter().flatten() {
+            let (path, expected) = match info {
+                Value::String(path) => (path.clone(), json!([])),
+                Value::Object(info) => (
+                    info.get("path")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    convert_expected(info.get("expected")),
+                ),
+                _ => continue,
+            };
+            if path.is_empty() || path.starts_with(':') {
+                warn!("题解 {}/{} 的路径 {} 无法迁移，已跳过", user, algo, path);
+                continue;
+            }
+            let is_std = user == "std" && algo == "std";
+            let key = if is_std {
+                "std".to_string()
+            } else {
+                format!("{}/{}", user, algo)
+            };
+            let expected = if is_std && expected == json!([]) {
+                json!("== 100")
+            } else {
+                expected
+            };
+            tests.insert(key, json!({ "expected": expected, "path": path }));
+        }
+    }
+
+    let memory_limit = conf
+        .get("memory limit")
+        .and_then(|v| v.as_str())
+        .and_then(|text| text.parse::<ByteSize>().ok())
+        .unwrap_or(ByteSize::mib(512));
+
+    let mut result = json!({
+        "version": 3,
+        "folder": "problem",
+        "type": conf.get("type").and_then(|v| v.as_str()).unwrap_or("program"),
+        "name": name,
+        "title": localized(conf.get("title")),
+        "time limit": conf.get("time limit").and_then(|v| v.as_f64()).unwrap_or(1.0),
+        "memory limit": memory_limit.to_string(),
+        "partial score": conf.get("partial score").and_then(|v| v.as_bool()).unwrap_or(false),
+        "samples": samples,
+        "data": data,
+        "subtasks": subtasks,
+    });
+    if let Some(args) = conf.get("args").and_then(|v| v.as_object())
+        && !args.is_empty()
+    {
+        result["args"] = Value::Object(args.clone());
+    }
+    if !tests.is_empty() {
+        result["tests"] = Value::Object(tests);
+    }
+    if dir.join("data").join("chk").join("chk.cpp").exists() {
+        result["use-chk"] = json!(true);
+    }
+
+    // 题面：tuack 放在 statement/<语言>.md
+    let statement = dir.join("statement.md");
+    if !statement.exists()
+        && let Some(source) = [
+            dir.join("statement").join("zh-cn.md"),
+            dir.join("statement").join("en.md"),
+            dir.join("description.md"),
+        ]
+        .into_iter()
+        .find(|path| path.exists())
+    {
+        fs::copy(&source, &statement)?;
+        warn!(
+            "题目 {} 的题面已复制到 statement.md，tuack 的模板语法与 tuack-ng 不同，请手动检查",
+            name
+        );
+    }
+    if dir.join("tables").exists() {
+        warn!("题目 {} 的 tables 目录需要手动改写为 tuack-ng 模板", name);
+    }
+
+    Ok(result)
+}
+
+/// 递归迁移目录，返回该目录的类型
+fn migrate_dir(dir: &Path, compile: &mut Option<Value>) -> Result<String> {
+    let Some((conf, legacy_path)) = read_legacy_config(dir)? else {
+        info!("{} 已是 tuack-ng 格式，跳过", dir.display());
+        return Ok(String::new());
+    };
+
+    let folder = conf
+        .get("folder")
+        .and_then(|v| v.as_str())
+        .unwrap_or("problem")
+        .to_string();
+
+    let name = conf
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| dir_name(dir));
+
+    let result = match folder.as_str() {
+        "contest" => {
+            for sub in subdirs(&conf) {
+                migrate_dir(&dir.join(sub), compile)?;
+            }
+            json!({
+                "version": 3,
+                "folder": "contest",
+                "name": name,
+                "subdir": subdirs(&conf),
+                "title": localized(conf.get("title")),
+                "short title": localized(conf.get("short title")),
+            })
+        }
+        "day" => {
+            // tuack 的编译选项写在题目中，tuack-ng 写在竞赛日中
+            let mut day_compile = None;
+            for sub in subdirs(&conf) {
+                migrate_dir(&dir.join(sub), &mut day_compile)?;
+            }
+            json!({
+                "version": 3,
+                "folder": "day",
+                "name": name,
+                "subdir": subdirs(&conf),
+                "title": localized(conf.get("title")),
+                "compile": day_compile.unwrap_or(json!({ "cpp": "-O2 -std=c++14 -static" })),
+                "start time": convert_time(conf.get("start time")),
+                "end time": convert_time(conf.get("end time")),
+            })
+        }
+        "problem" => {
+            if let Some(problem_compile) = conf.get("compile") {
+                match compile {
+                    Some(existing) if existing != problem_compile => {
+                        warn!(
+                            "题目 {} 的编译选项与同场其他题目不同，将使用第一道题目的设置",
+                            name
+                        )
+                    }
+                    Some(_) => {}
+                    None => *compile = Some(problem_compile.clone()),
+                }
+            }
+            migrate_problem(dir, &conf)?
+        }
+        other => bail!("不支持迁移 {} 类型的目录: {}", other, dir.display()),
+    };
+
+    // 使用 tuack-ng 的配置结构校验迁移结果
+    let content = match folder.as_str() {
+        "contest" => save_contest_config(&serde_json::from_value(result)?)?,
+        "day" => save_day_config(&serde_json::from_value(result)?)?,
+        _ => save_problem_config(&serde_json::from_value(result)?)?,
+    };
+    write_config(dir, &legacy_path, content)?;
+
+    Ok(folder)
+}
+
+pub fn main(args: MigrateArgs) -> Result<()> {
+    if args.path.join("probs.json").exists() && !args.path.join(CONFIG_FILE_NAME).exists() {
+        bail!("probs.json 是 tuack 更早期的格式，请先使用 `python -m tuack.gen upgrade` 升级");
+    }
+
+    let folder = migrate_dir(&args.path, &mut None)?;
+
+    if !folder.is_empty() && folder != "contest" {
+        warn!("迁移的根目录是 {}，tuack-ng 需要在竞赛目录下使用", folder);
+    }
+    info!("迁移完成");
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use crate::prelude::*;
+use crate::ren::resolve;
+use clap::{Args, ValueEnum};
+use export::PdfTemplate;
+use indexmap::IndexMap;
+
+pub mod export;
+pub mod import;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SourceFormat {
+    /// 每行一条成绩，包含选手、题目、得分与可选的用时（毫秒）
+    Csv,
+    /// Lemon 导出的成绩表
+    Lemon,
+    /// Arbiter 导出的成绩表
+    Arbiter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Tiebreak {
+    /// 总分相同的选手名次相同
+    None,
+    /// 总分相同时总用时少者优先
+    Time,
+}
+
+#[derive(Args, Debug)]
+#[command(version)]
+pub struct RankArgs {
+    /// 成绩文件，在比赛目录下执行时以 <竞赛日>=<路径> 指定所属的竞赛日
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+
+    /// 成绩文件的格式
+    #[arg(short, long, default_value = "csv")]
+    pub format: SourceFormat,
+
+    /// 选手名单，包含考号与姓名两列的 CSV 文件
+    #[arg(short, long)]
+    pub roster: Option<PathBuf>,
+
+    /// 同分时的排名方式
+    #[arg(short, long, default_value = "none")]
+    pub tiebreak: Tiebreak,
+
+    /// 输出目录，默认为当前目录下的 ranking
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// 使用成绩单模板生成可打印的 PDF
+    #[arg(long)]
+    pub pdf: bool,
+
+    /// 生成 PDF 使用的模板，模板中的 main.typ 读取 data.json 中的 title、header 与 rows；
+    /// 未指定时使用名为 rank 的模板，没有时使用内置的成绩单
+    #[arg(long)]
+    pub template: Option<String>,
+}
+
+/// 一名选手在一道题目上的成绩
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Score {
+    pub score: u32,
+    /// 用时（毫秒），成绩来源没有用时信息时为 None
+    pub time_ms: Option<u64>,
+}
+
+/// 一个竞赛日的成绩，按考号与题目名称索引
+pub type DayScores = BTreeMap<String, HashMap<String, Score>>;
+
+/// 选手名单，按考号索引姓名
+pub type Roster = IndexMap<String, String>;
+
+/// 排名表中的一行
+#[derive(Debug)]
+pub struct StandingRow {
+    pub rank: usize,
+    pub id: String,
+    pub name: String,
+    pub scores: Vec<Option<u32>>,
+    pub total: u32,
+    pub time_ms: Option<u64>,
+}
+
+/// 排名表，`columns` 为各题目或各竞赛日
+#[derive(Debug)]
+pub struct Standings {
+    pub title: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<StandingRow>,
+    pub tiebreak: Tiebreak,
+}
+
+impl Standings {
+    /// 按总分排序并计算名次，`rows` 中的名次会被覆盖
+    pub fn new(
+        title: String,
+        columns: Vec<String>,
+        mut rows: Vec<StandingRow>,
+        tiebreak: Tiebreak,
+    ) -> Self {
+        let use_time = tiebreak == Tiebreak::Time;
+        let time_key = |row: &StandingRow| row.time_ms.unwrap_or(u64::MAX);
+
+        rows.sort_by(|a, b| {
+            let order = b.total.cmp(&a.total);
+            let order = if use_time {
+                order.then_with(|| time_key(a).cmp(&time_key(b)))
+            } else {
+                order
+            };
+            order.then_with(|| natord::compare(&a.id, &b.id))
+        });
+
+        for i in 0..rows.len() {
+            let tied = i > 0
+                && rows[i - 1].total == rows[i].total
+                && (!use_time || time_key(&rows[i - 1]) == time_key(&rows[i]));
+            rows[i].rank = if tied { rows[i - 1].rank } else { i + 1 };
+        }
+
+        Standings {
+            title,
+            columns,
+            rows,
+            tiebreak,
+        }
+    }
+}
+
+/// 选手的姓名，名单中没有时使用考号
+fn player_name(roster: Option<&Roster>, id: &str) -> String {
+    roster
+        .and_then(|roster| roster.get(id))
+        .cloned()
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// 参与排名的选手：名单中的选手以及有成绩的选手
+fn player_ids<'a>(
+    roster: Option<&'a Roster>,
+    scores: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    let mut ids: Vec<String> = roster
+        .map(|roster| roster.keys().cloned().collect())
+        .unwrap_or_default();
+    for id in scores {
+        if !ids.contains(id) {
+            if roster.is_some() {
+                warn!("考号 {} 不在选手名单中", id);
+            }
+            ids.push(id.clone());
+        }
+    }
+    ids
+}
+
+/// 生成一个竞赛日的排名表，每道题目一列
+fn day_standings(
+    day: &ContestDayConfig,
+    scores: &DayScores,
+    roster: Option<&Roster>,
+    tiebreak: Tiebreak,
+) -> Standings {
+    let problems: Vec<&ProblemConfig> = day.subconfig.values().collect();
+    let rows = player_ids(roster, scores.keys())
+        .into_iter()
+        .map(|id| {
+            let player_scores = scores.get(&id);
+            let problem_scores: Vec<Option<&Score>> = problems
+                .iter()
+                .map(|prob| player_scores.and_then(|s| s.get(&prob.name)))
+                .collect();
+            // 没有任何成绩的选手不参与用时比较
+            let times: Option<Vec<u64>> = problem_scores
+                .iter()
+                .flatten()
+                .map(|score| score.time_ms)
+                .collect::<Option<Vec<u64>>>()
+                .filter(|times| !times.is_empty());
+            StandingRow {
+                rank: 0,
+                name: player_name(roster, &id),
+                id,
+                scores: problem_scores
+                    .iter()
+                    .map(|score| score.map(|s| s.score))
+                    .collect(),
+                total: problem_scores.iter().flatten().map(|s| s.score).sum(),
+                time_ms: times.map(|times| times.iter().sum()),
+            }
+        })
+        .collect();
+
+    let title = if day.title.is_empty() {
+        day.name.clone()
+    } else {
+        day.title.clone()
+    };
+    Standings::new(
+        title,
+        problems.iter().map(|prob| prob.name.clone()).collect(),
+        rows,
+        tiebreak,
+    )
+}
+
+/// 将各竞赛日的排名表汇总为整场比赛的排名表，每个竞赛日一列
+fn contest_standings(
+    contest: &ContestConfig,
+    days: &[Standings],
+    day_names: Vec<String>,
+    roster: Option<&Roster>,
+    tiebreak: Tiebreak,
+) -> Standings {
+    let ids = player_ids(
+        roster,
+        days.iter()
+            .flat_map(|day| day.rows.iter().map(|row| &row.id)),
+    );
+    let rows = ids
+        .into_iter()
+        .map(|id| {
+            let day_rows: Vec<Option<&StandingRow>> = days
+                .iter()
+                .map(|day| day.rows.iter().find(|row| row.id == id))
+                .collect();
+            let times: Option<Vec<u64>> = day_rows
+                .iter()
+                .map(|row| row.and_then(|row| row.time_ms))
+                .collect();
+            StandingRow {
+                rank: 0,
+                name: player_name(roster, &id),
+                id,
+                scores: day_rows.iter().map(|row| row.map(|r| r.total)).collect(),
+                total: day_rows.iter().flatten().map(|row| row.total).sum(),
+                time_ms: times.map(|times| times.iter().sum()),
+            }
+        })
+        .collect();
+
+    Standings::new(contest.title.clone(), day_names, rows, tiebreak)
+}
+
+pub fn main(args: RankArgs) -> Result<()> {
+    let (config, current_location) = get_context().config.as_ref().context("找不到配置文件")?;
+
+    let (days, base_dir): (Vec<&ContestDayConfig>, PathBuf) = match current_location {
+        CurrentLocation::None => bail!("此命令必须在工程下执行"),
+        CurrentLocation::Root => (config.subconfig.values().collect(), config.path.clone()),
+        CurrentLocation::Day(day_name) | CurrentLocation::Problem(day_name, _) => {
+            let day = config
+                .subconfig
+                .get(day_name)
+                .with_context(|| format!("未找到天配置: {}", day_name))?;
+            (vec![day], day.path.clone())
+        }
+    };
+
+    let roster = match &args.roster {
+        Some(path) => Some(import::read_roster(path)?),
+        None => None,
+    };
+
+    // 读取成绩文件，同一竞赛日可以有多个文件
+    let mut scores: IndexMap<String, DayScores> = IndexMap::new();
+    for input in &args.inputs {
+        let (day, path) = match input
+            .split_once('=')
+            .and_then(|(name, path)| Some((days.iter().find(|day| day.name == name)?, path)))
+        {
+            Some((day, path)) => (*day, PathBuf::from(path)),
+            None if days.len() == 1 => (days[0], PathBuf::from(input)),
+            None => bail!("请以 <竞赛日>=<路径> 的形式指定成绩文件: {}", input),
+        };
+        info!("读取竞赛日 {} 的成绩: {}", day.name, path.display());
+        let day_scores = import::read_scores(args.format, &path, day)?;
+        let entry = scores.entry(day.name.clone()).or_default();
+        for (id, problems) in day_scores {
+            entry.entry(id).or_default().extend(problems);
+        }
+    }
+
+    // 在写入任何文件之前确定成绩单模板，指定的模板不存在时直接报错
+    let pdf_template = match &args.template {
+        _ if !args.pdf => None,
+        Some(template) => Some(PdfTemplate::Layers(resolve::template_layers(
+            config, template,
+        )?)),
+        None if resolve::template_exists(config, "rank") => Some(PdfTemplate::Layers(
+            resolve::template_layers(config, "rank")?,
+        )),
+        None => {
+            info!("没有找到模板 rank，使用内置的成绩单");
+            Some(PdfTemplate::Builtin)
+        }
+    };
+
+    let output_dir = args
+        .output
+        .clone()
+        .unwrap_or_else(|| base_dir.join("ranking"));
+    fs::create_dir_all(&output_dir)?;
+
+    let mut day_standings_list = Vec::new();
+    let mut day_names = Vec::new();
+    for day in &days {
+        let Some(day_scores) = scores.get(&day.name) else {
+            warn!("竞赛日 {} 没有成绩，跳过", day.name);
+            continue;
+        };
+        let standings = day_standings(day, day_scores, roster.as_ref(), args.tiebreak);
+        export::write_all(&standings, &output_dir, &day.name, pdf_template.as_ref())?;
+        day_standings_list.push(standings);
+        day_names.push(day.name.clone());
+    }
+
+    if matches!(current_location, CurrentLocation::Root) && !day_standings_list.is_empty() {
+        let standings = contest_standings(
+            config,
+            &day_standings_list,
+            day_names,
+            roster.as_ref(),
+            args.tiebreak,
+        );
+        export::write_all(&standings, &output_dir, "total", pdf_template.as_ref())?;
+    }
+
+    info!("排名已保存到 {}", output_dir.display());
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use super::{Standings, Tiebreak};
+use crate::prelude::*;
+use crate::ren::find_fonts_dir;
+use crate::ren::renderers::typst::typst_compile;
+use crate::ren::resolve::assemble_template;
+use crate::utils::filesystem::copy_dir_recursive;
+use csv::Writer;
+
+/// 排名表的表头与各行的单元格
+fn table(standings: &Standings) -> (Vec<String>, Vec<Vec<String>>) {
+    let with_time = standings.tiebreak == Tiebreak::Time;
+
+    let mut header = vec!["排名".to_string(), "考号".to_string(), "姓名".to_string()];
+    header.extend(standings.columns.iter().cloned());
+    header.push("总分".to_string());
+    if with_time {
+        header.push("总用时(ms)".to_string());
+    }
+
+    let rows = standings
+        .rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.rank.to_string(), row.id.clone(), row.name.clone()];
+            cells.extend(row.scores.iter().map(|score| match score {
+                Some(score) => score.to_string(),
+                None => "-".to_string(),
+            }));
+            cells.push(row.total.to_string());
+            if with_time {
+                cells.push(match row.time_ms {
+                    Some(time) => time.to_string(),
+                    None => "-".to_string(),
+                });
+            }
+            cells
+        })
+        .collect();
+
+    (header, rows)
+}
+
+pub fn write_csv(standings: &Standings, path: &Path) -> Result<()> {
+    let (header, rows) = table(standings);
+    let mut wtr = Writer::from_path(path)?;
+    wtr.write_record(&header)?;
+    for row in &rows {
+        wtr.write_record(row)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn write_html(standings: &Standings, path: &Path) -> Result<()> {
+    let (header, rows) = table(standings);
+    let title = html_escape(&format!("{} 成绩", standings.title));
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str(concat!(
+        "<style>\n",
+        "body { font-family: sans-serif; }\n",
+        "table { border-collapse: collapse; }\n",
+        "th, td { border: 1px solid #888; padding: 4px 12px; text-align: center; }\n",
+        "tr:nth-child(even) { background: #f4f4f4; }\n",
+        "</style>\n",
+    ));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n<table>\n<tr>", title));
+    for cell in &header {
+        html.push_str(&format!("<th>{}</th>", html_escape(cell)));
+    }
+    html.push_str("</tr>\n");
+    for row in &rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", html_escape(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+
+    fs::write(path, html)?;
+    Ok(())
+}
+
+/// 成绩单模板读取的 data.json
+#[derive(Serialize)]
+struct RankData {
+    /// 排名表的标题，如比赛或竞赛日的名称
+    title: String,
+    /// 表头
+    header: Vec<String>,
+    /// 各行的单元格，已按名次排列
+    rows: Vec<Vec<String>>,
+}
+
+/// 没有成绩单模板时使用的 main.typ，也是自定义模板读取 data.json 的示例
+const BUILTIN_TEMPLATE: &str = r#"#let data = json("data.json")
+#set page(paper: "a4", margin: 1.5cm, numbering: "1 / 1")
+#set text(lang: "zh", size: 10pt)
+#align(center, text(size: 16pt, weight: "bold", data.title + " 成绩"))
+
+#table(
+  columns: data.header.len(),
+  align: center,
+  table.header(..data.header.map(cell => strong(cell))),
+  ..data.rows.flatten(),
+)
+"#;
+
+/// PDF 成绩单使用的模板
+pub enum PdfTemplate {
+    /// 模板及其继承的各层模板目录，合并后模板中的 main.typ 读取同一目录下的 data.json
+    Layers(Vec<PathBuf>),
+    /// 内置的成绩单
+    Builtin,
+}
+
+/// 使用成绩单模板生成 PDF
+pub fn write_pdf(standings: &Standings, template: &PdfTemplate, path: &Path) -> Result<()> {
+    let (header, rows) = table(standings);
+
+    let tmp_dir = path.with_extension("tmp");
+    match template {
+        PdfTemplate::Layers(layers) => {
+            assemble_template(layers, &tmp_dir)?;
+            if !tmp_dir.join("main.typ").exists() {
+                bail!(
+                    "成绩单模板缺少 main.typ: {}",
+                    layers.last().unwrap().display()
+                );
+            }
+        }
+        PdfTemplate::Builtin => {
+            if tmp_dir.exists() {
+                fs::remove_dir_all(&tmp_dir)?;
+            }
+            fs::create_dir_all(&tmp_dir)?;
+            fs::write(tmp_dir.join("main.typ"), BUILTIN_TEMPLATE)?;
+        }
+    }
+    copy_dir_recursive(&find_fonts_dir()?, tmp_dir.join("fonts"))?;
+
+    let data = RankData {
+        title: standings.title.clone(),
+        header,
+        rows,
+    };
+    fs::write(
+        tmp_dir.join("data.json"),
+        serde_json::to_string_pretty(&data)?,
+    )?;
+
+    typst_compile(&tmp_dir, "main.typ", "ranking.pdf")?;
+    fs::copy(tmp_dir.join("ranking.pdf"), path)?;
+    fs::remove_dir_all(&tmp_dir)?;
+
+    Ok(())
+}
+
+/// 写入排名表的 CSV、HTML 以及可选的 PDF，文件名为 `stem`
+///
+/// `pdf_template` 为 None 时不生成 PDF。
+pub fn write_all(
+    standings: &Standings,
+    output_dir: &Path,
+    stem: &str,
+    pdf_template: Option<&PdfTemplate>,
+) -> Result<()> {
+    write_csv(standings, &output_dir.join(format!("{}.csv", stem)))?;
+    write_html(standings, &output_dir.join(format!("{}.html", stem)))?;
+    if let Some(template) = pdf_template {
+        write_pdf(
+            standings,
+            template,
+            &output_dir.join(format!("{}.pdf", stem)),
+        )?;
+    }
+    info!("已生成 {} 的排名", standings.title);
+    Ok(())
+}
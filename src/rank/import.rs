@@ -0,0 +1,147 @@
+use super::{DayScores, Roster, Score, SourceFormat};
+use crate::prelude::*;
+use csv::{Reader, StringRecord};
+
+/// 考号一列可能使用的表头
+const ID_HEADERS: [&str; 5] = ["id", "考号", "准考证号", "编号", "选手"];
+/// 姓名一列可能使用的表头
+const NAME_HEADERS: [&str; 3] = ["name", "姓名", "名字"];
+/// Lemon 成绩表中选手一列可能使用的表头
+const LEMON_HEADERS: [&str; 4] = ["name", "contestant", "选手", "姓名"];
+/// Arbiter 成绩表中选手一列可能使用的表头
+const ARBITER_HEADERS: [&str; 3] = ["考号", "准考证号", "id"];
+
+/// 查找表头中第一个匹配的列，不区分大小写
+fn find_column(headers: &StringRecord, names: &[&str]) -> Option<usize> {
+    names.iter().find_map(|name| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    })
+}
+
+fn open(path: &Path) -> Result<(Reader<fs::File>, StringRecord)> {
+    let mut reader =
+        Reader::from_path(path).with_context(|| format!("无法打开 {}", path.display()))?;
+    let headers = reader.headers()?.clone();
+    Ok((reader, headers))
+}
+
+/// 读取选手名单
+pub fn read_roster(path: &Path) -> Result<Roster> {
+    let (mut reader, headers) = open(path)?;
+    let id_col = find_column(&headers, &ID_HEADERS)
+        .with_context(|| format!("选手名单 {} 中没有考号一列", path.display()))?;
+    let name_col = find_column(&headers, &NAME_HEADERS)
+        .with_context(|| format!("选手名单 {} 中没有姓名一列", path.display()))?;
+
+    let mut roster = Roster::new();
+    for record in reader.records() {
+        let record = record?;
+        let id = record.get(id_col).unwrap_or("").trim();
+        if id.is_empty() {
+            continue;
+        }
+        let name = record.get(name_col).unwrap_or("").trim();
+        if roster.insert(id.to_string(), name.to_string()).is_some() {
+            warn!("选手名单中考号 {} 重复", id);
+        }
+    }
+    info!("选手名单共 {} 人", roster.len());
+
+    Ok(roster)
+}
+
+/// 将成绩解析为整数，非数字（如未评测）时返回 None
+fn parse_score(text: &str) -> Option<u32> {
+    let value: f64 = text.trim().parse().ok()?;
+    Some(value.max(0.0).round() as u32)
+}
+
+/// 读取每行一条成绩的 CSV 文件
+fn read_long(path: &Path, day: &ContestDayConfig) -> Result<DayScores> {
+    let (mut reader, headers) = open(path)?;
+    let id_col = find_column(&headers, &ID_HEADERS)
+        .with_context(|| format!("{} 中没有选手一列", path.display()))?;
+    let problem_col = find_column(&headers, &["problem", "题目"])
+        .with_context(|| format!("{} 中没有题目一列", path.display()))?;
+    let score_col = find_column(&headers, &["score", "得分"])
+        .with_context(|| format!("{} 中没有得分一列", path.display()))?;
+    let time_col = find_column(&headers, &["time", "用时"]);
+
+    let mut scores = DayScores::new();
+    for record in reader.records() {
+        let record = record?;
+        let id = record.get(id_col).unwrap_or("").trim();
+        let problem = record.get(problem_col).unwrap_or("").trim();
+        if id.is_empty() {
+            continue;
+        }
+        if !day.subconfig.values().any(|prob| prob.name == problem) {
+            warn!("竞赛日 {} 中没有题目 {}，已忽略", day.name, problem);
+            continue;
+        }
+        let Some(score) = parse_score(record.get(score_col).unwrap_or("")) else {
+            warn!("选手 {} 的题目 {} 没有有效的得分，已忽略", id, problem);
+            continue;
+        };
+        let time_ms = time_col
+            .and_then(|col| record.get(col))
+            .and_then(|text| text.trim().parse().ok());
+        scores
+            .entry(id.to_string())
+            .or_default()
+            .insert(problem.to_string(), Score { score, time_ms });
+    }
+
+    Ok(scores)
+}
+
+/// 读取评测软件导出的成绩表，每名选手一行，每道题目一列
+///
+/// 题目列的表头可以是题目名称或标题，其余列（名次、总分等）会被忽略。
+fn read_wide(path: &Path, day: &ContestDayConfig, id_headers: &[&str]) -> Result<DayScores> {
+    let (mut reader, headers) = open(path)?;
+    let id_col = find_column(&headers, id_headers)
+        .with_context(|| format!("{} 中没有选手一列", path.display()))?;
+
+    let mut columns = Vec::new();
+    for prob in day.subconfig.values() {
+        match find_column(&headers, &[prob.name.as_str(), prob.title.as_str()]) {
+            Some(col) => columns.push((col, prob.name.clone())),
+            None => warn!("{} 中没有题目 {} 的成绩", path.display(), prob.name),
+        }
+    }
+
+    let mut scores = DayScores::new();
+    for record in reader.records() {
+        let record = record?;
+        let id = record.get(id_col).unwrap_or("").trim();
+        if id.is_empty() {
+            continue;
+        }
+        let entry = scores.entry(id.to_string()).or_default();
+        for (col, problem) in &columns {
+            if let Some(score) = parse_score(record.get(*col).unwrap_or("")) {
+                entry.insert(
+                    problem.clone(),
+                    Score {
+                        score,
+                        time_ms: None,
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(scores)
+}
+
+/// 读取一个竞赛日的成绩文件
+pub fn read_scores(format: SourceFormat, path: &Path, day: &ContestDayConfig) -> Result<DayScores> {
+    match format {
+        SourceFormat::Csv => read_long(path, day),
+        SourceFormat::Lemon => read_wide(path, day, &LEMON_HEADERS),
+        SourceFormat::Arbiter => read_wide(path, day, &ARBITER_HEADERS),
+    }
+}
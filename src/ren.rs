@@ -1,22 +1,27 @@
 use crate::prelude::*;
+pub mod lint;
+pub mod locale;
 pub mod renderers;
+pub mod resolve;
 pub mod template;
 pub mod utils;
+pub mod watch;
 use crate::config::TargetType;
 use crate::config::TemplateManifest;
 use crate::ren::renderers::base::Checker;
 use crate::ren::renderers::base::Compiler;
+use crate::ren::renderers::html::{HtmlChecker, HtmlCompiler};
+use crate::ren::renderers::latex::{LatexChecker, LatexCompiler};
 use crate::ren::renderers::markdown::MarkdownChecker;
 use crate::ren::renderers::markdown::MarkdownCompiler;
-use crate::ren::renderers::typst::{TypstChecker, TypstCompiler};
-use clap::Args;
+use crate::ren::renderers::typst::{TypstChecker, TypstCompiler, compile_booklet};
+use clap::{Args, ValueEnum};
 use indexmap::IndexMap;
 use markdown_ppp::ast::Document;
-use markdown_ppp::parser::*;
 use std::time::Duration;
 
 use template::render_template;
-use utils::{process_image_urls, process_images_with_unique_ids};
+use utils::{parse_statement, process_image_urls, process_images_with_unique_ids};
 
 use crate::utils::filesystem::copy_dir_recursive;
 
@@ -35,70 +40,211 @@ pub struct RenArgs {
     /// 保留临时目录用于调试
     #[arg(long)]
     pub keep_tmp: bool,
+
+    /// 监视题面、配置与模板的修改并自动重新渲染
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// 渲染题解（solution.md），使用模板中的 solution 部分
+    #[arg(short, long)]
+    pub solution: bool,
+
+    /// 渲染的语言，如 zh-cn、en，可指定多个，每种语言单独输出
+    #[arg(short, long, value_delimiter = ',')]
+    pub lang: Vec<String>,
+
+    /// 只检查题面中的常见错误，不渲染
+    #[arg(long, conflicts_with = "watch")]
+    pub check: bool,
+
+    /// 输出的文档，可指定多个，未指定时每个竞赛日输出一份（仅 Typst 模板，problem、cover 与 precaution 还需模板清单声明 parts）
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub layout: Vec<Layout>,
 }
 
-pub enum RenderQueue {
-    Problem(Document, Box<ProblemConfig>),
-    Precaution(Document),
+/// `ren` 输出的文档
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Layout {
+    /// 每个竞赛日一份完整的题面：<竞赛日>.pdf
+    Day,
+    /// 每道题目一份，不含封面与注意事项：<竞赛日>-<题目>.pdf
+    Problem,
+    /// 只含封面：<竞赛日>-cover.pdf
+    Cover,
+    /// 只含注意事项：<竞赛日>-precaution.pdf
+    Precaution,
+    /// 所有竞赛日合订为一份：<比赛>.pdf
+    Contest,
 }
 
-pub fn main(args: RenArgs) -> Result<()> {
-    debug!(
-        "当前目录: {}",
-        dunce::canonicalize(Path::new("."))?.to_string_lossy()
-    );
+impl RenArgs {
+    /// 每道题目中要渲染的文件名（不含语言与扩展名）
+    pub(crate) fn source_stem(&self) -> &'static str {
+        if self.solution {
+            "solution"
+        } else {
+            "statement"
+        }
+    }
 
-    let (config, current_location) = get_context().config.as_ref().context("找不到配置文件")?;
+    /// 每道题目中存放图片的目录
+    pub(crate) fn image_dir(&self) -> &'static str {
+        if self.solution { "solution" } else { "img" }
+    }
 
-    // 根据当前位置确定skip_level和目标配置的键
-    let (skip_level, target_day_key, target_problem_key) = match current_location {
-        CurrentLocation::Problem(day_name, problem_name) => {
-            (2, Some(day_name.as_str()), Some(problem_name.as_str()))
+    /// 渲染结果的输出目录
+    fn output_dir(&self) -> &'static str {
+        if self.solution {
+            "solutions"
+        } else {
+            "statements"
         }
-        CurrentLocation::Day(day_name) => (1, Some(day_name.as_str()), None),
-        _ => (0, None, None),
-    };
+    }
+}
 
-    let template_dir = context::get_context().assets_dirs.iter().find(|dir| {
-        let subdir = dir.join("templates").join(&args.target);
-        subdir.exists() && subdir.is_dir()
-    });
+/// 一次渲染的选项，由命令行决定，不属于模板清单
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// 当前渲染的语言，None 表示不带语言的源文件
+    pub language: Option<String>,
+    /// 要输出的文档
+    pub layouts: Vec<Layout>,
+    /// 每道题目中渲染的文件名（不含语言与扩展名），见 [`RenArgs::source_stem`]
+    pub source_stem: &'static str,
+}
 
-    let template_dir = match template_dir {
-        Some(dir) => {
-            info!(
-                "找到模板目录: {}",
-                dir.join("templates").join(&args.target).to_string_lossy()
-            );
-            dir.join("templates").join(&args.target)
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            language: None,
+            layouts: Vec::new(),
+            source_stem: "statement",
         }
-        None => {
-            error!("没有找到模板 {}", args.target);
-            bail!("没有找到模板 {}", args.target);
+    }
+}
+
+pub enum RenderQueue {
+    Problem(Document, Box<ProblemConfig>),
+    Precaution(Document),
+}
+
+/// 查找 `dir` 中指定语言的 Markdown 文件，如 `statement.en.md`，不存在时使用 `statement.md`
+pub(crate) fn localized_file(dir: &Path, stem: &str, language: Option<&str>) -> PathBuf {
+    if let Some(language) = language {
+        let path = dir.join(format!("{}.{}.md", stem, language));
+        if path.exists() {
+            return path;
         }
-    };
+        debug!("未找到 {}，使用 {}.md", path.display(), stem);
+    }
+    dir.join(format!("{}.md", stem))
+}
+
+/// 当前位置下需要渲染的竞赛日与题目
+pub(crate) fn problems_in_scope<'a>(
+    config: &'a ContestConfig,
+    current_location: &CurrentLocation,
+) -> Vec<(&'a ContestDayConfig, Vec<&'a ProblemConfig>)> {
+    config
+        .subconfig
+        .values()
+        .filter_map(|day| {
+            let problems: Vec<&ProblemConfig> = match current_location {
+                CurrentLocation::Day(day_name) if *day_name != day.name => return None,
+                CurrentLocation::Problem(day_name, problem_name) => {
+                    if *day_name != day.name {
+                        return None;
+                    }
+                    day.subconfig.get(problem_name).into_iter().collect()
+                }
+                _ => day.subconfig.values().collect(),
+            };
+            Some((day, problems))
+        })
+        .collect()
+}
 
+/// 查找模板使用的字体目录
+pub(crate) fn find_fonts_dir() -> Result<PathBuf> {
     let fonts_dir = context::get_context().assets_dirs.iter().find(|dir| {
         let subdir = dir.join("templates").join("fonts");
         subdir.exists() && subdir.is_dir()
     });
 
-    let fonts_dir = match fonts_dir {
+    match fonts_dir {
         Some(dir) => {
             info!(
                 "找到字体目录: {}",
                 dir.join("templates").join("fonts").to_string_lossy()
             );
-            dir.join("templates").join("fonts")
+            Ok(dir.join("templates").join("fonts"))
         }
         None => {
             error!("没有找到字体目录");
-            bail!("致命错误: 没有找到模板 {}", args.target);
+            bail!("致命错误: 没有找到字体目录");
+        }
+    }
+}
+
+pub fn main(args: RenArgs) -> Result<()> {
+    debug!(
+        "当前目录: {}",
+        dunce::canonicalize(Path::new("."))?.to_string_lossy()
+    );
+
+    let (config, current_location) = get_context().config.as_ref().context("找不到配置文件")?;
+
+    if args.watch {
+        return watch::main(&args, config.clone(), current_location.clone());
+    }
+
+    render(&args, config, current_location, None)
+}
+
+/// 渲染当前位置下的题面，`only_day` 指定时只渲染该竞赛日
+pub(crate) fn render(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+    only_day: Option<&str>,
+) -> Result<()> {
+    // 有继承关系时将各层模板合并到输出目录下的临时目录
+    let layers = resolve::template_layers(config, &args.target)?;
+    let assembled_dir = if layers.len() > 1 {
+        let dir = output_base_dir(args, config, current_location)
+            .join(&args.target)
+            .join("template");
+        resolve::assemble_template(&layers, &dir)?;
+        Some(dir)
+    } else {
+        None
+    };
+    let template_dir = assembled_dir.clone().unwrap_or_else(|| layers[0].clone());
+
+    // 题解使用模板中单独的 solution 部分，清单文件缺省时沿用题面的清单
+    let (template_dir, manifest_dir) = if args.solution {
+        let solution_dir = template_dir.join("solution");
+        if !solution_dir.is_dir() {
+            error!("模板 {} 没有题解部分", args.target);
+            bail!(
+                "模板 {} 没有题解部分: {}",
+                args.target,
+                solution_dir.display()
+            );
         }
+        if solution_dir.join("manifest.json").exists() {
+            (solution_dir.clone(), solution_dir)
+        } else {
+            (solution_dir, template_dir)
+        }
+    } else {
+        (template_dir.clone(), template_dir)
     };
 
+    let fonts_dir = find_fonts_dir()?;
+
     let manifest = {
-        let manifest_file = template_dir.join("manifest.json");
+        let manifest_file = manifest_dir.join("manifest.json");
         if manifest_file.exists() {
             let manifest_content = fs::read_to_string(&manifest_file)?;
             serde_json::from_str::<TemplateManifest>(&manifest_content)?
@@ -108,35 +254,143 @@ pub fn main(args: RenArgs) -> Result<()> {
         }
     };
 
-    let checker: Box<dyn Checker> = match manifest.target {
-        TargetType::Typst => Box::new(TypstChecker::new(template_dir.to_path_buf())),
-        TargetType::Markdown => Box::new(MarkdownChecker::new(template_dir.to_path_buf())),
+    // 命令行指定的语言优先，其次是模板清单中的语言，都没有时只渲染默认的 statement.md
+    let languages: Vec<Option<String>> = if !args.lang.is_empty() {
+        args.lang.iter().cloned().map(Some).collect()
+    } else if !manifest.languages.is_empty() {
+        manifest.languages.iter().cloned().map(Some).collect()
+    } else {
+        vec![None]
     };
-    checker.check_compiler()?;
 
-    let statements_dir = match current_location {
+    if args.check {
+        lint::main(args, config, current_location, &manifest, &languages)?;
+    } else {
+        let checker: Box<dyn Checker> = match manifest.target {
+            TargetType::Typst => Box::new(TypstChecker::new(template_dir.to_path_buf())),
+            TargetType::Markdown => Box::new(MarkdownChecker::new(template_dir.to_path_buf())),
+            TargetType::Html => Box::new(HtmlChecker::new(template_dir.to_path_buf())),
+            TargetType::Latex => Box::new(LatexChecker::new(template_dir.to_path_buf())),
+        };
+        checker.check_compiler()?;
+
+        let layouts = if args.layout.is_empty() {
+            vec![Layout::Day]
+        } else {
+            args.layout.clone()
+        };
+        if layouts != [Layout::Day] && !matches!(manifest.target, TargetType::Typst) {
+            error!("只有 Typst 模板支持 --layout");
+            bail!("模板 {} 不支持 --layout", args.target);
+        }
+        // 只输出部分内容需要模板读取 data.json 中的 parts
+        let partial = layouts
+            .iter()
+            .any(|layout| matches!(layout, Layout::Problem | Layout::Cover | Layout::Precaution));
+        if partial && !manifest.parts {
+            error!(
+                "模板 {} 的清单没有声明 parts，无法只输出部分内容",
+                args.target
+            );
+            bail!(
+                "模板 {} 不支持 --layout problem/cover/precaution",
+                args.target
+            );
+        }
+        if layouts.contains(&Layout::Contest) && !matches!(current_location, CurrentLocation::Root)
+        {
+            error!("只能在比赛目录下输出合订本");
+            bail!("只能在比赛目录下输出合订本");
+        }
+        for language in languages {
+            if let Some(language) = &language {
+                info!("渲染语言: {}", language);
+            }
+            render_language(
+                args,
+                config,
+                current_location,
+                only_day,
+                &template_dir,
+                &fonts_dir,
+                manifest.clone(),
+                RenderOptions {
+                    language,
+                    layouts: layouts.clone(),
+                    source_stem: args.source_stem(),
+                },
+            )?;
+        }
+    }
+
+    if let Some(dir) = assembled_dir
+        && !args.keep_tmp
+    {
+        fs::remove_dir_all(dir)?;
+    }
+
+    Ok(())
+}
+
+/// 当前位置对应的输出目录
+fn output_base_dir(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+) -> PathBuf {
+    match current_location {
         CurrentLocation::Problem(day_name, problem_name) => Path::new(&config.path)
             .join(day_name)
             .join(problem_name)
-            .join("statements"),
-        CurrentLocation::Day(day_name) => Path::new(&config.path).join(day_name).join("statements"),
-        _ => config.path.join("statements"),
+            .join(args.output_dir()),
+        CurrentLocation::Day(day_name) => Path::new(&config.path)
+            .join(day_name)
+            .join(args.output_dir()),
+        _ => config.path.join(args.output_dir()),
+    }
+}
+
+/// 以 `options.language` 指定的语言渲染题面
+#[allow(clippy::too_many_arguments)]
+fn render_language(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+    only_day: Option<&str>,
+    template_dir: &Path,
+    fonts_dir: &Path,
+    manifest: TemplateManifest,
+    options: RenderOptions,
+) -> Result<()> {
+    // 根据当前位置确定skip_level和目标配置的键
+    let (skip_level, target_day_key, target_problem_key) = match current_location {
+        CurrentLocation::Problem(day_name, problem_name) => {
+            (2, Some(day_name.as_str()), Some(problem_name.as_str()))
+        }
+        CurrentLocation::Day(day_name) => (1, Some(day_name.as_str()), None),
+        _ => (0, only_day, None),
     };
 
+    let statements_dir = output_base_dir(args, config, current_location);
+
     info!("{}", &statements_dir.to_string_lossy());
     if !statements_dir.exists() {
         info!("创建题面输出目录: {}", statements_dir.display());
         fs::create_dir(&statements_dir)?;
     }
 
-    let statements_dir = statements_dir.join(&args.target);
+    // 多语言时每种语言输出到单独的子目录
+    let statements_dir = match &options.language {
+        Some(language) => statements_dir.join(&args.target).join(language),
+        None => statements_dir.join(&args.target),
+    };
     if !statements_dir.exists() {
         info!(
             "创建 {} 目标输出目录: {}",
             args.target,
             statements_dir.display()
         );
-        fs::create_dir(&statements_dir)?;
+        fs::create_dir_all(&statements_dir)?;
     }
 
     // 获取要处理的天配置
@@ -176,6 +430,25 @@ pub fn main(args: RenArgs) -> Result<()> {
         pb
     };
 
+    // 合订本将各竞赛日的临时目录收集到同一目录下再编译
+    let booklet_dir = if options.layouts.contains(&Layout::Contest) {
+        if only_day.is_some() {
+            info!("只重新渲染部分竞赛日，不更新合订本");
+            None
+        } else {
+            let dir = statements_dir.join("booklet");
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            fs::create_dir_all(&dir)?;
+            copy_dir_recursive(fonts_dir, dir.join("fonts"))?;
+            Some(dir)
+        }
+    } else {
+        None
+    };
+    let mut booklet_days = Vec::new();
+
     let mut day_count = 0;
     for (_day_key, day_config) in days_to_process {
         day_count += 1;
@@ -199,7 +472,7 @@ pub fn main(args: RenArgs) -> Result<()> {
         info!("创建临时目录: {}", tmp_dir.display());
 
         info!("复制模板文件到临时目录");
-        copy_dir_recursive(&template_dir, &tmp_dir)?;
+        copy_dir_recursive(template_dir, &tmp_dir)?;
 
         let tmp_font_dir = tmp_dir.join("fonts");
         if tmp_font_dir.exists() {
@@ -207,7 +480,7 @@ pub fn main(args: RenArgs) -> Result<()> {
         }
 
         info!("复制字体文件到临时目录");
-        copy_dir_recursive(&fonts_dir, &tmp_font_dir)?;
+        copy_dir_recursive(fonts_dir, &tmp_font_dir)?;
 
         // 获取要渲染的问题
         let problems_to_render: IndexMap<String, &ProblemConfig> =
@@ -279,7 +552,8 @@ pub fn main(args: RenArgs) -> Result<()> {
 
             // 题面文件路径
             let problem_dir = &problem_config.path;
-            let statement_path = problem_dir.join("statement.md");
+            let statement_path =
+                localized_file(problem_dir, args.source_stem(), options.language.as_deref());
 
             if !statement_path.exists() {
                 error!("未找到题面文件: {}", statement_path.display());
@@ -295,6 +569,7 @@ pub fn main(args: RenArgs) -> Result<()> {
                 config,
                 problem_config.path.clone(),
                 manifest.clone(),
+                &options,
             ) {
                 Ok(content) => content,
                 Err(e) => {
@@ -308,17 +583,16 @@ pub fn main(args: RenArgs) -> Result<()> {
                 }
             };
 
-            let state = MarkdownParserState::new();
-            let mut ast = match parse_markdown(state, &content) {
+            let mut ast = match parse_statement(&content) {
                 Ok(ast) => ast,
                 Err(e) => {
-                    error!("解析题面文件 {} 失败: {:?}", statement_path.display(), e);
+                    error!("解析题面文件 {} 失败: {:#}", statement_path.display(), e);
                     problem_pb.finish_with_message("遇到错误，停止处理");
                     bail!("解析题面文件失败");
                 }
             };
 
-            let img_src_dir = problem_dir.join("img");
+            let img_src_dir = problem_dir.join(args.image_dir());
 
             process_image_urls(&img_src_dir, &mut ast);
 
@@ -344,19 +618,21 @@ pub fn main(args: RenArgs) -> Result<()> {
             problem_pb.inc(1);
         }
 
-        // 处理注意事项文件
-        let precaution_path = config.path.join("precaution.md");
+        // 处理注意事项文件，题解没有注意事项
+        let precaution_path =
+            localized_file(&config.path, "precaution", options.language.as_deref());
         info!("{}", precaution_path.to_string_lossy());
-        if precaution_path.exists() {
+        if args.solution {
+            debug!("渲染题解，跳过注意事项");
+        } else if precaution_path.exists() {
             info!("处理注意事项文件: {}", precaution_path.display());
             let content = fs::read_to_string(&precaution_path)?;
-            let state = MarkdownParserState::new();
-            match parse_markdown(state, &content) {
+            match parse_statement(&content) {
                 Ok(ast) => {
                     renderqueue.push(RenderQueue::Precaution(ast));
                 }
                 Err(e) => {
-                    warn!("解析注意事项文件失败: {:?}", e);
+                    warn!("解析注意事项文件失败: {:#}", e);
                 }
             }
         } else {
@@ -376,6 +652,7 @@ pub fn main(args: RenArgs) -> Result<()> {
                 tmp_dir.clone(),
                 renderqueue,
                 manifest.clone(),
+                options.clone(),
             )),
             TargetType::Markdown => Box::new(MarkdownCompiler::new(
                 config.clone(),
@@ -383,6 +660,23 @@ pub fn main(args: RenArgs) -> Result<()> {
                 tmp_dir.clone(),
                 renderqueue,
                 manifest.clone(),
+                options.clone(),
+            )),
+            TargetType::Html => Box::new(HtmlCompiler::new(
+                config.clone(),
+                day_to_render,
+                tmp_dir.clone(),
+                renderqueue,
+                manifest.clone(),
+                options.clone(),
+            )),
+            TargetType::Latex => Box::new(LatexCompiler::new(
+                config.clone(),
+                day_to_render,
+                tmp_dir.clone(),
+                renderqueue,
+                manifest.clone(),
+                options.clone(),
             )),
         };
 
@@ -412,6 +706,14 @@ pub fn main(args: RenArgs) -> Result<()> {
             }
             info!("PDF已保存到: {}", target.display());
 
+            if let Some(booklet_dir) = &booklet_dir {
+                let day_dir = booklet_dir.join(&day_config.name);
+                copy_dir_recursive(&tmp_dir, &day_dir)?;
+                fs::remove_dir_all(day_dir.join("output"))?;
+                fs::remove_dir_all(day_dir.join("fonts"))?;
+                booklet_days.push(day_config.name.clone());
+            }
+
             if args.keep_tmp {
                 info!("保留临时目录: {}", tmp_dir.display());
             } else {
@@ -432,6 +734,21 @@ pub fn main(args: RenArgs) -> Result<()> {
         }
     }
 
+    if let Some(booklet_dir) = booklet_dir {
+        info!("编译合订本: {}", config.name);
+        let output_filename = format!("{}.pdf", config.name);
+        compile_booklet(&booklet_dir, &booklet_days, &output_filename)?;
+        let target = statements_dir.join(&output_filename);
+        fs::copy(booklet_dir.join(&output_filename), &target)?;
+        info!("合订本已保存到: {}", target.display());
+
+        if args.keep_tmp {
+            info!("保留合订本临时目录: {}", booklet_dir.display());
+        } else {
+            fs::remove_dir_all(&booklet_dir)?;
+        }
+    }
+
     if skip_level == 0 {
         day_pb.finish_with_message("渲染完成！");
     } else {
@@ -0,0 +1,326 @@
+use super::template::{render_template, render_template_strict};
+use super::utils::{inline_text, parse_statement, source_math};
+use super::{RenArgs, RenderOptions, localized_file, problems_in_scope};
+use crate::config::{ProblemType, TemplateManifest};
+use crate::prelude::*;
+use crate::ren::locale::{self, Locale};
+use markdown_ppp::ast::{Block, Document};
+use markdown_ppp::ast_transform::Transform;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// 收集文档中所有标题的文字与代码块的内容
+fn collect_blocks(blocks: &[Block], headings: &mut Vec<String>, code_blocks: &mut Vec<String>) {
+    for block in blocks {
+        match block {
+            Block::Heading(heading) => headings.push(inline_text(&heading.content)),
+            Block::CodeBlock(code) => code_blocks.push(code.literal.clone()),
+            Block::BlockQuote(children) => collect_blocks(children, headings, code_blocks),
+            Block::List(list) => {
+                for item in &list.items {
+                    collect_blocks(&item.blocks, headings, code_blocks);
+                }
+            }
+            Block::GitHubAlert(alert) => collect_blocks(&alert.blocks, headings, code_blocks),
+            _ => {}
+        }
+    }
+}
+
+/// 去掉行尾空白与末尾空行，用于比较样例
+fn normalize(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(|line| line.trim_end()).collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |idx| idx + 1);
+    lines[..end].join("\n")
+}
+
+/// 检查公式中的大括号、`\left`/`\right` 与 `\begin`/`\end` 是否配对
+fn check_math(formula: &str) -> Option<String> {
+    let mut depth = 0;
+    let mut chars = formula.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth < 0 {
+                    return Some("多余的 }".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return Some("缺少 }".to_string());
+    }
+
+    let delimiter = Regex::new(r"\\(left|right)([^a-zA-Z]|$)").unwrap();
+    let lefts = delimiter
+        .captures_iter(formula)
+        .filter(|caps| &caps[1] == "left")
+        .count();
+    let rights = delimiter.captures_iter(formula).count() - lefts;
+    if lefts != rights {
+        return Some(format!(
+            "\\left 与 \\right 数量不一致 ({}/{})",
+            lefts, rights
+        ));
+    }
+
+    let environment = Regex::new(r"\\(begin|end)\{([^}]*)\}").unwrap();
+    let mut stack = Vec::new();
+    for caps in environment.captures_iter(formula) {
+        let name = caps[2].to_string();
+        if &caps[1] == "begin" {
+            stack.push(name);
+        } else if stack.pop().as_ref() != Some(&name) {
+            return Some(format!("\\end{{{}}} 没有对应的 \\begin", name));
+        }
+    }
+    if let Some(name) = stack.pop() {
+        return Some(format!("\\begin{{{}}} 没有对应的 \\end", name));
+    }
+
+    None
+}
+
+/// 检查 `sample.text(n)`、`sample.file(n)` 引用的样例是否存在
+fn check_sample_calls(source: &str, problem: &ProblemConfig, issues: &mut Vec<String>) {
+    let call = Regex::new(r"sample\s*\.\s*(text|file)\s*\(\s*(\d+)\s*\)").unwrap();
+    for caps in call.captures_iter(source) {
+        let id: u32 = caps[2].parse().unwrap_or(0);
+        if !problem.samples.iter().any(|sample| sample.id == id) {
+            issues.push(format!(
+                "sample.{}({}) 引用的样例 {} 不在 samples 中",
+                &caps[1], id, id
+            ));
+        }
+    }
+}
+
+/// 检查图片是否位于图片目录中且存在
+fn check_images(ast: &Document, img_src_dir: &Path, issues: &mut Vec<String>) {
+    let dir_name = img_src_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("img");
+    let prefix = format!("{}/", dir_name);
+    let dot_prefix = format!("./{}/", dir_name);
+
+    let urls = RefCell::new(Vec::new());
+    ast.clone().transform_image_urls(|url| {
+        urls.borrow_mut().push(url.clone());
+        url
+    });
+    for url in urls.into_inner() {
+        if url.contains("://") || url.starts_with("data:") {
+            continue;
+        }
+        if !url.starts_with(&prefix) && !url.starts_with(&dot_prefix) {
+            issues.push(format!("图片 {} 不在 {} 目录中", url, prefix));
+            continue;
+        }
+        let filename = Path::new(&url).file_name().unwrap_or_default();
+        if !img_src_dir.join(filename).exists() {
+            issues.push(format!("图片 {} 不存在", url));
+        }
+    }
+}
+
+/// 检查公式语法，直接检查展开后的源文件，`\$` 与代码中的 `$` 不视为公式
+fn check_formulas(content: &str, issues: &mut Vec<String>) {
+    let (formulas, unclosed) = source_math(content);
+    for context in unclosed {
+        issues.push(format!("存在未闭合的 $: {}", context.trim()));
+    }
+    for (formula, _) in formulas {
+        if let Some(message) = check_math(&formula) {
+            issues.push(format!("公式 {} 有误: {}", formula, message));
+        }
+    }
+}
+
+/// 检查题面是否包含必需的小节
+fn check_sections(
+    headings: &[String],
+    problem: &ProblemConfig,
+    locale: &Locale,
+    issues: &mut Vec<String>,
+) {
+    let mut required = vec![locale.constraints];
+    // 只有传统题一定有输入输出格式
+    if matches!(problem.problem_type, ProblemType::Program) {
+        required.insert(0, locale.output_format);
+        required.insert(0, locale.input_format);
+    }
+    for section in required {
+        if !headings
+            .iter()
+            .any(|heading| heading.trim().starts_with(section))
+        {
+            issues.push(format!("缺少小节: {}", section));
+        }
+    }
+}
+
+/// 检查题面中的代码块与样例文件是否一致
+///
+/// 首行与某个样例文件相同而其余内容不同的代码块，视为复制后未同步修改的样例。
+fn check_code_blocks(code_blocks: &[String], problem: &ProblemConfig, issues: &mut Vec<String>) {
+    let mut sample_files = Vec::new();
+    for sample in &problem.samples {
+        for file in [sample.input.get(), sample.output.get()]
+            .into_iter()
+            .flatten()
+        {
+            let path = problem.path.join("sample").join(file);
+            match fs::read_to_string(&path) {
+                Ok(content) => sample_files.push((file.clone(), normalize(&content))),
+                Err(_) => issues.push(format!("样例文件 {} 不存在", path.display())),
+            }
+        }
+    }
+
+    for block in code_blocks {
+        let block = normalize(block);
+        let Some(first_line) = block.lines().next() else {
+            continue;
+        };
+        if sample_files.iter().any(|(_, content)| *content == block) {
+            continue;
+        }
+        if let Some((file, content)) = sample_files
+            .iter()
+            .find(|(_, content)| content.lines().next() == Some(first_line))
+        {
+            let line = block
+                .lines()
+                .zip(content.lines())
+                .position(|(a, b)| a != b)
+                .unwrap_or_else(|| block.lines().count().min(content.lines().count()))
+                + 1;
+            issues.push(format!("代码块与样例文件 {} 不一致 (第 {} 行)", file, line));
+        }
+    }
+}
+
+/// 检查一份题面，返回发现的问题
+fn lint_file(
+    args: &RenArgs,
+    source_path: &Path,
+    problem: &ProblemConfig,
+    day: &ContestDayConfig,
+    config: &ContestConfig,
+    manifest: &TemplateManifest,
+    options: &RenderOptions,
+) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+    let locale = locale::get(options.language.as_deref());
+    let source = fs::read_to_string(source_path)?;
+
+    check_sample_calls(&source, problem, &mut issues);
+
+    let content = match render_template_strict(
+        &source,
+        problem,
+        day,
+        config,
+        problem.path.clone(),
+        manifest.clone(),
+        options,
+    ) {
+        Ok(content) => content,
+        Err(e) => {
+            issues.push(format!("模板展开失败: {:#}", e));
+            // 继续用宽松模式展开，以便检查其余内容
+            match render_template(
+                &source,
+                problem,
+                day,
+                config,
+                problem.path.clone(),
+                manifest.clone(),
+                options,
+            ) {
+                Ok(content) => content,
+                Err(_) => return Ok(issues),
+            }
+        }
+    };
+
+    let ast = match parse_statement(&content) {
+        Ok(ast) => ast,
+        Err(e) => {
+            issues.push(format!("解析 Markdown 失败: {:#}", e));
+            return Ok(issues);
+        }
+    };
+
+    let mut headings = Vec::new();
+    let mut code_blocks = Vec::new();
+    collect_blocks(&ast.blocks, &mut headings, &mut code_blocks);
+
+    check_images(&ast, &problem.path.join(args.image_dir()), &mut issues);
+    check_formulas(&content, &mut issues);
+    if !args.solution {
+        check_sections(&headings, problem, locale, &mut issues);
+    }
+    check_code_blocks(&code_blocks, problem, &mut issues);
+
+    Ok(issues)
+}
+
+/// 检查当前位置下所有题面，有问题时返回错误
+pub fn main(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+    manifest: &TemplateManifest,
+    languages: &[Option<String>],
+) -> Result<()> {
+    let mut checked = HashSet::new();
+    let mut total = 0;
+
+    for (day, problems) in problems_in_scope(config, current_location) {
+        for problem in problems {
+            for language in languages {
+                let source_path =
+                    localized_file(&problem.path, args.source_stem(), language.as_deref());
+                if !checked.insert(source_path.clone()) {
+                    continue;
+                }
+                if !source_path.exists() {
+                    error!("未找到题面文件: {}", source_path.display());
+                    total += 1;
+                    continue;
+                }
+
+                info!("检查题面: {}", source_path.display());
+                let options = RenderOptions {
+                    language: language.clone(),
+                    source_stem: args.source_stem(),
+                    ..Default::default()
+                };
+                let issues =
+                    lint_file(args, &source_path, problem, day, config, manifest, &options)?;
+                for issue in &issues {
+                    error!("{}: {}", source_path.display(), issue);
+                }
+                total += issues.len();
+            }
+        }
+    }
+
+    if total > 0 {
+        bail!("题面检查发现 {} 个问题", total);
+    }
+    info!("题面检查通过");
+    Ok(())
+}
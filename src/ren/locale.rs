@@ -0,0 +1,98 @@
+use crate::prelude::*;
+
+/// 题面中由程序生成的文字，`{name}`、`{id}` 等会被替换
+pub struct Locale {
+    pub input_file: &'static str,
+    pub input_stdin: &'static str,
+    pub output_file: &'static str,
+    pub output_stdout: &'static str,
+    pub sample_file: &'static str,
+    pub sample_input: &'static str,
+    pub sample_output: &'static str,
+    pub program: &'static str,
+    pub output: &'static str,
+    pub interactive: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub seconds: &'static str,
+    pub precaution: &'static str,
+    pub subtask: &'static str,
+    pub testcase: &'static str,
+    pub score: &'static str,
+    pub testcase_id: &'static str,
+    pub special: &'static str,
+    pub none: &'static str,
+    pub input_format: &'static str,
+    pub output_format: &'static str,
+    pub constraints: &'static str,
+}
+
+const ZH_CN: Locale = Locale {
+    input_file: "从文件 _{name}.in_ 中读入数据。",
+    input_stdin: "从标准输入读入数据。",
+    output_file: "输出到文件 _{name}.out_ 中。",
+    output_stdout: "输出到标准输出。",
+    sample_file: "见选手目录下的 _{name}/{name}{id}.in_ 与 _{name}/{name}{id}.ans_。",
+    sample_input: "样例 {id} 输入",
+    sample_output: "样例 {id} 输出",
+    program: "传统型",
+    output: "提交答案型",
+    interactive: "交互型",
+    yes: "是",
+    no: "否",
+    seconds: "{value} 秒",
+    precaution: "注意事项",
+    subtask: "子任务",
+    testcase: "测试点",
+    score: "分值",
+    testcase_id: "测试点编号",
+    special: "特殊性质",
+    none: "无",
+    input_format: "输入格式",
+    output_format: "输出格式",
+    constraints: "数据范围",
+};
+
+const EN: Locale = Locale {
+    input_file: "Read the input from the file _{name}.in_.",
+    input_stdin: "Read the input from standard input.",
+    output_file: "Write the output to the file _{name}.out_.",
+    output_stdout: "Write the output to standard output.",
+    sample_file: "See _{name}/{name}{id}.in_ and _{name}/{name}{id}.ans_ in the contestant directory.",
+    sample_input: "Sample Input {id}",
+    sample_output: "Sample Output {id}",
+    program: "Traditional",
+    output: "Output only",
+    interactive: "Interactive",
+    yes: "Yes",
+    no: "No",
+    seconds: "{value} s",
+    precaution: "Notes",
+    subtask: "Subtask",
+    testcase: "Test cases",
+    score: "Score",
+    testcase_id: "Test case",
+    special: "Special property",
+    none: "None",
+    input_format: "Input",
+    output_format: "Output",
+    constraints: "Constraints",
+};
+
+/// 获取语言对应的文字，未指定语言时使用简体中文
+///
+/// 语言代码不区分大小写，`en-us` 等地区变体使用对应语言的文字。
+pub fn get(language: Option<&str>) -> &'static Locale {
+    let Some(language) = language else {
+        return &ZH_CN;
+    };
+    let language = language.to_lowercase();
+    match language.split(['-', '_']).next() {
+        Some("zh") => &ZH_CN,
+        Some("en") => &EN,
+        _ => {
+            warn!("不支持语言 {} 的内置文字，使用简体中文", language);
+            &ZH_CN
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod base;
+pub mod html;
+pub mod latex;
 pub mod markdown;
 pub mod typst;
@@ -1,6 +1,9 @@
-use crate::config::TemplateManifest;
+use crate::config::{
+    DataJson, DateInfo, DocumentParts, Problem, SupportLanguage, TemplateManifest,
+};
 use crate::prelude::*;
-use crate::ren::RenderQueue;
+use crate::ren::locale;
+use crate::ren::{RenderOptions, RenderQueue};
 
 pub trait Checker {
     fn check_compiler(&self) -> Result<()>;
@@ -17,7 +20,128 @@ pub trait Compiler {
         tmp_dir: PathBuf,
         renderqueue: Vec<RenderQueue>,
         manifest: TemplateManifest,
+        options: RenderOptions,
     ) -> Self
     where
         Self: Sized;
 }
+
+/// 构建模板使用的比赛信息，即 data.json 的内容
+pub fn build_data_json(
+    contest_config: &ContestConfig,
+    day_config: &ContestDayConfig,
+    manifest: &TemplateManifest,
+    options: &RenderOptions,
+) -> Result<DataJson> {
+    let locale = locale::get(options.language.as_deref());
+
+    // 构建问题列表
+    let mut problems = Vec::new();
+
+    for (_name, problem_config) in &day_config.subconfig {
+        let mut submit_filenames = Vec::new();
+
+        // 遍历 day_config.compile 中的语言配置来生成对应的提交文件名
+        for lang_key in day_config.compile.keys() {
+            submit_filenames.push(format!("{}.{}", problem_config.name, lang_key));
+        }
+
+        let point_equal = if problem_config.data.is_empty() {
+            // 如果没有测试数据，默认为"是"
+            locale.yes.to_string()
+        } else {
+            // 获取第一个测试点的分数
+            let first_score = problem_config.data[0].score;
+            // 检查所有测试点的分数是否都等于第一个测试点的分数
+            let all_equal = problem_config
+                .data
+                .iter()
+                .all(|data_item| data_item.score == first_score);
+
+            if all_equal {
+                locale.yes.to_string()
+            } else {
+                locale.no.to_string()
+            }
+        };
+
+        let problem = Problem {
+            name: problem_config.name.clone(),
+            title: problem_config.title.clone(),
+            dir: problem_config.name.clone(), // 假设目录名就是问题名
+            exec: problem_config.name.clone(), // 默认值，你可能需要从配置文件读取
+            input: problem_config.name.clone() + ".in",
+            output: problem_config.name.clone() + ".out",
+            problem_type: match problem_config.problem_type {
+                ProblemType::Program => locale.program,
+                ProblemType::Output => locale.output,
+                ProblemType::Interactive => locale.interactive,
+            }
+            .to_string(),
+            time_limit: locale
+                .seconds
+                .replace("{value}", &format!("{:.1}", problem_config.time_limit)),
+            memory_limit: format!("{:.0}", problem_config.memory_limit),
+            testcase: problem_config.data.len().to_string(),
+            point_equal,
+            submit_filename: submit_filenames,
+        };
+        problems.push(problem);
+    }
+
+    // 构建支持的语言列表
+    let context = crate::context::get_context();
+    let mut support_languages = Vec::new();
+
+    for (lang_key, compile_options) in &day_config.compile {
+        // 从context中查找对应的语言配置来获取语言名称
+        let language_name = if let Some(lang_config) = context.languages.get(lang_key) {
+            lang_config.language.clone()
+        } else {
+            // 如果context中没有对应的语言配置，使用键名作为语言名称
+            error!("在语言配置中未找到 {}", lang_key);
+            bail!("在语言配置中未找到 {}", lang_key);
+        };
+
+        let language = SupportLanguage {
+            name: language_name,
+            compile_options: compile_options.clone(),
+        };
+        support_languages.push(language);
+    }
+
+    // 创建日期信息
+    let date = DateInfo {
+        start: day_config.start_time,
+        end: day_config.end_time,
+    };
+
+    // 从ContestConfig和ContestDayConfig中获取覆盖值
+    let use_pretest = day_config
+        .use_pretest
+        .or(contest_config.use_pretest)
+        .unwrap_or(manifest.use_pretest);
+    let noi_style = day_config
+        .noi_style
+        .or(contest_config.noi_style)
+        .unwrap_or(manifest.noi_style);
+    let file_io = day_config
+        .file_io
+        .or(contest_config.file_io)
+        .unwrap_or(manifest.file_io);
+    let data_json = DataJson {
+        title: contest_config.title.clone(),
+        subtitle: contest_config.short_title.clone(),
+        dayname: day_config.title.clone(),
+        date,
+        use_pretest,
+        noi_style,
+        file_io,
+        support_languages,
+        problems,
+        language: options.language.clone(),
+        parts: DocumentParts::all(),
+    };
+
+    Ok(data_json)
+}
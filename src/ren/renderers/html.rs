@@ -0,0 +1,278 @@
+use crate::config::TemplateManifest;
+use crate::prelude::*;
+use crate::ren::Compiler;
+use crate::ren::RenderOptions;
+use crate::ren::RenderQueue;
+use crate::ren::locale;
+use crate::ren::renderers::base::Checker;
+use crate::ren::utils::{extract_math, math_placeholder};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use latex2mathml::{DisplayStyle, latex_to_mathml};
+use markdown_ppp::ast::Document;
+use markdown_ppp::ast_transform::Transform;
+use markdown_ppp::html_printer::config::Config;
+use markdown_ppp::html_printer::render_html;
+use regex::{Captures, Regex};
+use std::cell::RefCell;
+
+/// 模板未提供 style.css 时使用的样式，包含打印样式
+const DEFAULT_CSS: &str = r#"body {
+    max-width: 860px;
+    margin: 2em auto;
+    padding: 0 1em;
+    font-family: "Noto Serif CJK SC", "Source Han Serif SC", serif;
+    line-height: 1.7;
+}
+pre, code {
+    font-family: "Fira Code", "Noto Sans Mono", monospace;
+    background: #f5f5f5;
+}
+pre {
+    padding: 0.8em;
+    overflow-x: auto;
+}
+table {
+    border-collapse: collapse;
+}
+th, td {
+    border: 1px solid #999;
+    padding: 0.3em 0.8em;
+}
+img {
+    max-width: 100%;
+}
+math[display="block"] {
+    margin: 0.8em 0;
+}
+@page {
+    size: A4;
+    margin: 2cm;
+}
+@media print {
+    body {
+        max-width: none;
+        margin: 0;
+        padding: 0;
+    }
+    pre {
+        white-space: pre-wrap;
+    }
+    a {
+        color: inherit;
+        text-decoration: none;
+    }
+    pre, table, img, math[display="block"] {
+        page-break-inside: avoid;
+    }
+    h1, h2, h3 {
+        page-break-after: avoid;
+    }
+    nav {
+        display: none;
+    }
+}
+"#;
+
+pub struct HtmlChecker {}
+
+impl Checker for HtmlChecker {
+    fn new(_: PathBuf) -> Self {
+        HtmlChecker {}
+    }
+
+    fn check_compiler(&self) -> Result<()> {
+        // 公式在本地转换为 MathML，不需要外部工具
+        Ok(())
+    }
+}
+
+pub struct HtmlCompiler {
+    pub contest_config: ContestConfig,
+    pub day_config: ContestDayConfig,
+    pub tmp_dir: PathBuf,
+    pub renderqueue: Vec<RenderQueue>,
+    pub options: RenderOptions,
+}
+
+impl Compiler for HtmlCompiler {
+    fn new(
+        contest_config: ContestConfig,
+        day_config: ContestDayConfig,
+        tmp_dir: PathBuf,
+        renderqueue: Vec<RenderQueue>,
+        _manifest: TemplateManifest,
+        options: RenderOptions,
+    ) -> Self {
+        HtmlCompiler {
+            contest_config,
+            day_config,
+            tmp_dir,
+            renderqueue,
+            options,
+        }
+    }
+
+    fn compile(&self) -> Result<PathBuf> {
+        let output_dir = &self.tmp_dir.join("output");
+        if !output_dir.exists() {
+            fs::create_dir_all(output_dir)?;
+        }
+
+        // 模板可以用 style.css 替换默认样式
+        let css_path = self.tmp_dir.join("style.css");
+        let css = if css_path.exists() {
+            fs::read_to_string(&css_path)?
+        } else {
+            DEFAULT_CSS.to_string()
+        };
+
+        let locale = locale::get(self.options.language.as_deref());
+        let lang = self.options.language.as_deref().unwrap_or("zh-CN");
+
+        let mut links = Vec::new();
+        for item in &self.renderqueue {
+            let (ast, title, filename) = match item {
+                RenderQueue::Problem(ast, problem_config) => (
+                    ast,
+                    problem_config.title.clone(),
+                    format!("{}.html", problem_config.name),
+                ),
+                RenderQueue::Precaution(ast) => (
+                    ast,
+                    locale.precaution.to_string(),
+                    "precaution.html".to_string(),
+                ),
+            };
+
+            let body = self.render_body(ast)?;
+            fs::write(output_dir.join(&filename), page(lang, &title, &css, &body))?;
+            info!("生成 HTML 文件: {}", filename);
+            links.push((title, filename));
+        }
+
+        // 索引页
+        let day_title = if self.day_config.title.is_empty() {
+            &self.day_config.name
+        } else {
+            &self.day_config.title
+        };
+        let mut index = format!(
+            "<h1>{}</h1>\n<h2>{}</h2>\n<nav>\n<ul>\n",
+            html_escape(&self.contest_config.title),
+            html_escape(day_title)
+        );
+        for (title, filename) in &links {
+            index.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                filename,
+                html_escape(title)
+            ));
+        }
+        index.push_str("</ul>\n</nav>\n");
+        fs::write(
+            output_dir.join("index.html"),
+            page(lang, &self.contest_config.title, &css, &index),
+        )?;
+
+        Ok(output_dir.clone())
+    }
+}
+
+impl HtmlCompiler {
+    /// 渲染题面正文，公式转换为 MathML，图片以 data URI 嵌入
+    fn render_body(&self, ast: &Document) -> Result<String> {
+        let formulas = RefCell::new(Vec::new());
+        let ast = ast
+            .clone()
+            .transform_text(|text| extract_math(&text, &mut formulas.borrow_mut()));
+        let formulas = formulas.into_inner();
+
+        let mut html = render_html(&ast, Config::default());
+
+        // 独占一段的行间公式不放在段落中
+        for (idx, (latex, display)) in formulas.iter().enumerate().rev() {
+            let mathml = render_math(latex, *display);
+            let holder = math_placeholder(idx);
+            if *display {
+                html = html.replace(&format!("<p>{}</p>", holder), &mathml);
+            }
+            html = html.replace(&holder, &mathml);
+        }
+
+        self.embed_images(&html)
+    }
+
+    /// 将 process_image_urls 改写后的图片路径替换为 data URI，使页面不依赖外部文件
+    fn embed_images(&self, html: &str) -> Result<String> {
+        let re = Regex::new(r#"src="(img/[^"]+)""#)?;
+        let mut error = None;
+        let html = re.replace_all(html, |caps: &Captures| {
+            let path = self.tmp_dir.join(&caps[1]);
+            match fs::read(&path) {
+                Ok(content) => format!(
+                    "src=\"data:{};base64,{}\"",
+                    mime_type(&path),
+                    STANDARD.encode(&content)
+                ),
+                Err(e) => {
+                    error = Some(anyhow!("无法读取图片 {}: {}", path.display(), e));
+                    caps[0].to_string()
+                }
+            }
+        });
+        match error {
+            Some(e) => Err(e),
+            None => Ok(html.into_owned()),
+        }
+    }
+}
+
+fn render_math(latex: &str, display: bool) -> String {
+    let style = if display {
+        DisplayStyle::Block
+    } else {
+        DisplayStyle::Inline
+    };
+    match latex_to_mathml(latex, style) {
+        Ok(mathml) => mathml,
+        Err(e) => {
+            warn!("无法转换公式 {}: {}", latex, e);
+            format!("<code class=\"math-error\">{}</code>", html_escape(latex))
+        }
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(lang: &str, title: &str, css: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        html_escape(lang),
+        html_escape(title),
+        css,
+        body
+    )
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("bmp") => "image/bmp",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,221 @@
+use super::base::Checker;
+use super::base::Compiler;
+use super::base::build_data_json;
+use crate::config::{ContestConfig, ContestDayConfig, TemplateManifest};
+use crate::prelude::*;
+use crate::ren::RenderOptions;
+use crate::ren::RenderQueue;
+use crate::ren::utils::{extract_math, math_placeholder};
+use crate::utils::filesystem::copy_dir_recursive;
+use markdown_ppp::ast::Document;
+use markdown_ppp::ast_transform::Transform;
+use markdown_ppp::latex_printer::config::Config;
+use markdown_ppp::latex_printer::render_latex;
+use minijinja::{Environment, Value};
+use std::cell::RefCell;
+use std::process::Command;
+
+/// 检查 xelatex 是否可用
+fn xelatex_available() -> bool {
+    Command::new("xelatex")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+pub struct LatexChecker {
+    pub template_dir: PathBuf,
+}
+
+impl Checker for LatexChecker {
+    fn new(template_dir: PathBuf) -> Self {
+        LatexChecker { template_dir }
+    }
+
+    fn check_compiler(&self) -> Result<()> {
+        debug!("检查LaTeX编译环境");
+        if !xelatex_available() {
+            warn!("未找到 xelatex 命令，将只生成 .tex 文件");
+        }
+
+        let template_required_files = ["main.tex.jinja"];
+        for file in template_required_files {
+            if !self.template_dir.join(file).exists() {
+                error!("模板缺少必要文件: {}", file);
+                bail!("模板缺少必要文件: {}", file);
+            }
+            info!("文件存在: {}", file);
+        }
+        Ok(())
+    }
+}
+
+pub struct LatexCompiler {
+    pub contest_config: ContestConfig,
+    pub day_config: ContestDayConfig,
+    pub tmp_dir: PathBuf,
+    pub renderqueue: Vec<RenderQueue>,
+    pub manifest: TemplateManifest,
+    pub options: RenderOptions,
+}
+
+impl Compiler for LatexCompiler {
+    fn new(
+        contest_config: ContestConfig,
+        day_config: ContestDayConfig,
+        tmp_dir: PathBuf,
+        renderqueue: Vec<RenderQueue>,
+        manifest: TemplateManifest,
+        options: RenderOptions,
+    ) -> Self {
+        LatexCompiler {
+            contest_config,
+            day_config,
+            tmp_dir,
+            renderqueue,
+            manifest,
+            options,
+        }
+    }
+
+    fn compile(&self) -> Result<PathBuf> {
+        let mut statements = Vec::new();
+        let mut precaution = None;
+        let mut render_idx: usize = 0;
+        for item in &self.renderqueue {
+            match item {
+                RenderQueue::Problem(ast, config) => {
+                    let filename = format!("problem-{}.tex", render_idx);
+                    info!("生成LaTeX: {}", config.name);
+                    fs::write(self.tmp_dir.join(&filename), render_body(ast))?;
+                    info!("生成: {}", filename);
+                    statements.push(serde_json::json!({
+                        "name": config.name,
+                        "title": config.title,
+                        "file": filename,
+                    }));
+                    render_idx += 1;
+                }
+                RenderQueue::Precaution(ast) => {
+                    info!("生成注意事项LaTeX...");
+                    fs::write(self.tmp_dir.join("precaution.tex"), render_body(ast))?;
+                    info!("生成: precaution.tex");
+                    precaution = Some("precaution.tex");
+                }
+            }
+        }
+
+        self.generate_main(statements, precaution)?;
+
+        let output_dir = self.tmp_dir.join("output");
+        fs::create_dir(&output_dir)?;
+
+        if !xelatex_available() {
+            // 没有 xelatex 时输出 .tex 源文件，由用户自行编译
+            for entry in fs::read_dir(&self.tmp_dir)? {
+                let path = entry?.path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                if name == "output"
+                    || name == "fonts"
+                    || name == "manifest.json"
+                    || name.ends_with(".jinja")
+                {
+                    continue;
+                }
+                if path.is_dir() {
+                    copy_dir_recursive(&path, output_dir.join(&name))?;
+                } else {
+                    fs::copy(&path, output_dir.join(&name))?;
+                }
+            }
+            warn!("未找到 xelatex，已输出 .tex 源文件");
+            return Ok(output_dir);
+        }
+
+        // 两次编译以生成目录与交叉引用
+        for _ in 0..2 {
+            xelatex_compile(&self.tmp_dir, "main.tex")?;
+        }
+        let output_filename = format!("output/{}.pdf", self.day_config.name);
+        fs::copy(
+            self.tmp_dir.join("main.pdf"),
+            self.tmp_dir.join(&output_filename),
+        )?;
+        Ok(self.tmp_dir.join(output_filename))
+    }
+}
+
+/// 在 `work_dir` 下使用 xelatex 编译 LaTeX 文件
+fn xelatex_compile(work_dir: &Path, input: &str) -> Result<()> {
+    let output = Command::new("xelatex")
+        .arg("-interaction=nonstopmode")
+        .arg("-halt-on-error")
+        .arg(input)
+        .current_dir(work_dir)
+        .output()?;
+    if output.status.success() {
+        info!("LaTeX 编译成功: {}", input);
+        Ok(())
+    } else {
+        // xelatex 将错误信息输出到 stdout
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        error!("LaTeX 编译失败: {}", stdout);
+        bail!("LaTeX 编译失败")
+    }
+}
+
+/// 将题面渲染为 LaTeX，公式原样保留
+fn render_body(ast: &Document) -> String {
+    render_body_with(ast, |formula, display| {
+        if display {
+            format!("\\[{}\\]", formula)
+        } else {
+            format!("${}$", formula)
+        }
+    })
+}
+
+/// 将题面渲染为 LaTeX，公式由 `math` 按是否为行间公式包装
+pub(crate) fn render_body_with(ast: &Document, math: impl Fn(&str, bool) -> String) -> String {
+    let formulas = RefCell::new(Vec::new());
+    let ast = ast
+        .clone()
+        .transform_text(|text| extract_math(&text, &mut formulas.borrow_mut()));
+    let formulas = formulas.into_inner();
+
+    let mut latex = render_latex(&ast, Config::default().with_width(1000000));
+
+    for (idx, (formula, display)) in formulas.iter().enumerate().rev() {
+        latex = latex.replace(&math_placeholder(idx), &math(formula, *display));
+    }
+
+    latex
+}
+
+impl LatexCompiler {
+    /// 使用与 data.json 相同的比赛信息展开 main.tex.jinja
+    fn generate_main(
+        &self,
+        statements: Vec<serde_json::Value>,
+        precaution: Option<&str>,
+    ) -> Result<()> {
+        let data_json = build_data_json(
+            &self.contest_config,
+            &self.day_config,
+            &self.manifest,
+            &self.options,
+        )?;
+        let mut context = serde_json::to_value(&data_json)?;
+        let object = context.as_object_mut().context("比赛信息不是 JSON 对象")?;
+        object.insert("statements".to_string(), statements.into());
+        object.insert("precaution".to_string(), precaution.into());
+
+        let template = fs::read_to_string(self.tmp_dir.join("main.tex.jinja"))?;
+        let env = Environment::new();
+        let main = env.render_str(&template, Value::from_serialize(&context))?;
+        fs::write(self.tmp_dir.join("main.tex"), main)?;
+        info!("生成 main.tex");
+
+        Ok(())
+    }
+}
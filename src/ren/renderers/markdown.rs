@@ -1,6 +1,7 @@
 use crate::config::TemplateManifest;
 use crate::prelude::*;
 use crate::ren::Compiler;
+use crate::ren::RenderOptions;
 use crate::ren::RenderQueue;
 use crate::ren::copy_dir_recursive;
 use crate::ren::renderers::base::Checker;
@@ -31,6 +32,7 @@ impl Compiler for MarkdownCompiler {
         tmp_dir: PathBuf,
         renderqueue: Vec<RenderQueue>,
         _manifest: TemplateManifest,
+        _options: RenderOptions,
     ) -> Self {
         MarkdownCompiler {
             tmp_dir,
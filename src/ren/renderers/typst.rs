@@ -1,16 +1,23 @@
 use super::base::Checker;
 use super::base::Compiler;
+use super::base::build_data_json;
 use crate::prelude::*;
+use crate::ren::RenderOptions;
 use crate::ren::RenderQueue;
+use crate::ren::localized_file;
 use markdown_ppp::ast::Document;
 use markdown_ppp::typst_printer::config::Config;
 use markdown_ppp::typst_printer::render_typst;
+use regex::Regex;
 use serde_json;
 use std::process::Command;
 
-use crate::config::{
-    ContestConfig, ContestDayConfig, DataJson, DateInfo, Problem, SupportLanguage, TemplateManifest,
-};
+use crate::config::{ContestConfig, ContestDayConfig, DocumentParts, TemplateManifest};
+use crate::ren::Layout;
+
+#[cfg(feature = "embedded-typst")]
+mod embedded;
+
 pub struct TypstChecker {
     pub template_dir: PathBuf,
 }
@@ -22,21 +29,13 @@ impl Checker for TypstChecker {
 
     fn check_compiler(&self) -> Result<()> {
         debug!("检查Typst编译环境");
-        let typst_check = Command::new("typst").arg("--version").output();
-
-        match typst_check {
-            Ok(output) => {
-                if output.status.success() {
-                    let version = String::from_utf8_lossy(&output.stdout);
-                    debug!("Typst 版本: {}", version.trim());
-                } else {
-                    error!("Typst 命令执行失败，请检查是否已安装");
-                    bail!("Typst 命令执行失败，请检查是否已安装");
-                }
-            }
-            Err(_) => {
-                bail!("未找到 typst 命令，请确保已安装并添加到PATH");
+        if cfg!(feature = "embedded-typst") {
+            debug!("使用内置的 Typst 编译器");
+            if !typst_command_available() {
+                debug!("PATH 中没有可用的 typst 命令，内置编译器失败时无法改用命令行编译");
             }
+        } else {
+            check_typst_command()?;
         }
 
         let template_required_files = ["main.typ", "utils.typ"];
@@ -51,12 +50,42 @@ impl Checker for TypstChecker {
     }
 }
 
+/// PATH 中是否有可用的 typst 命令，不输出日志
+fn typst_command_available() -> bool {
+    Command::new("typst")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// 检查 PATH 中的 typst 命令是否可用
+fn check_typst_command() -> Result<()> {
+    let typst_check = Command::new("typst").arg("--version").output();
+
+    match typst_check {
+        Ok(output) => {
+            if output.status.success() {
+                let version = String::from_utf8_lossy(&output.stdout);
+                debug!("Typst 版本: {}", version.trim());
+            } else {
+                error!("Typst 命令执行失败，请检查是否已安装");
+                bail!("Typst 命令执行失败，请检查是否已安装");
+            }
+        }
+        Err(_) => {
+            bail!("未找到 typst 命令，请确保已安装并添加到PATH");
+        }
+    }
+    Ok(())
+}
+
 pub struct TypstCompiler {
     pub contest_config: ContestConfig,
     pub day_config: ContestDayConfig,
     pub tmp_dir: PathBuf,
     pub renderqueue: Vec<RenderQueue>,
     pub manifest: TemplateManifest,
+    pub options: RenderOptions,
 }
 
 impl Compiler for TypstCompiler {
@@ -66,6 +95,7 @@ impl Compiler for TypstCompiler {
         tmp_dir: PathBuf,
         renderqueue: Vec<RenderQueue>,
         manifest: TemplateManifest,
+        options: RenderOptions,
     ) -> Self {
         TypstCompiler {
             contest_config,
@@ -73,147 +103,259 @@ impl Compiler for TypstCompiler {
             tmp_dir,
             renderqueue,
             manifest,
+            options,
         }
     }
     fn compile(&self) -> Result<PathBuf> {
-        self.generate_conf(&self.day_config, &self.tmp_dir)?;
-        let mut render_idx: usize = 0;
+        let mut problems = Vec::new();
+        let mut has_precaution = false;
         for item in &self.renderqueue {
             match item {
-                RenderQueue::Problem(ast, config) => {
-                    self.convert_ast(config, &self.tmp_dir, ast, render_idx)?;
-                    render_idx += 1;
-                }
+                RenderQueue::Problem(ast, config) => problems.push((ast, config.as_ref())),
                 RenderQueue::Precaution(ast) => {
                     self.convert_ast_precaution(&self.tmp_dir, ast)?;
+                    has_precaution = true;
                 }
             }
         }
-        fs::create_dir(self.tmp_dir.join("output"))?;
-        let output_filename = format!("output/{}.pdf", self.day_config.name);
-        let output = Command::new("typst")
-            .arg("compile")
-            .arg("--font-path=fonts")
-            .arg("main.typ")
-            .arg(&output_filename)
-            .current_dir(&self.tmp_dir)
-            .output()?;
-        if output.status.success() {
-            info!("Typst 编译成功: {}", output_filename);
-            Ok(self.tmp_dir.join(output_filename))
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            error!("Typst 编译失败: {}", stderr);
-            bail!("Typst 编译失败")
+
+        let output_dir = self.tmp_dir.join("output");
+        fs::create_dir(&output_dir)?;
+        let day = &self.day_config.name;
+        let only = |cover, precaution, problems| DocumentParts {
+            cover,
+            precaution,
+            problems,
+        };
+
+        let mut outputs = Vec::new();
+        for layout in &self.options.layouts {
+            match layout {
+                Layout::Day => outputs.push(self.compile_document(
+                    &problems,
+                    DocumentParts::all(),
+                    &format!("{}.pdf", day),
+                )?),
+                Layout::Problem => {
+                    for problem in &problems {
+                        outputs.push(self.compile_document(
+                            std::slice::from_ref(problem),
+                            only(false, false, true),
+                            &format!("{}-{}.pdf", day, problem.1.name),
+                        )?);
+                    }
+                }
+                Layout::Cover => outputs.push(self.compile_document(
+                    &problems,
+                    only(true, false, false),
+                    &format!("{}-cover.pdf", day),
+                )?),
+                Layout::Precaution if !has_precaution => {
+                    warn!("{} 没有注意事项，跳过", day);
+                }
+                Layout::Precaution => outputs.push(self.compile_document(
+                    &problems,
+                    only(false, true, false),
+                    &format!("{}-precaution.pdf", day),
+                )?),
+                // 合订本由 ren 在所有竞赛日渲染完成后生成
+                Layout::Contest => {}
+            }
+        }
+
+        // 合订本使用临时目录中的源文件，需要恢复为完整的题面
+        if self.options.layouts.contains(&Layout::Contest) {
+            self.write_sources(&problems, DocumentParts::all())?;
+        }
+
+        match outputs.as_slice() {
+            [output] => Ok(output.clone()),
+            _ => Ok(output_dir),
         }
     }
 }
-impl TypstCompiler {
-    fn generate_conf(&self, day_config: &ContestDayConfig, tmp_dir: &Path) -> Result<()> {
-        // 构建问题列表
-        let mut problems = Vec::new();
 
-        for (_name, problem_config) in &day_config.subconfig {
-            let mut submit_filenames = Vec::new();
+/// 将 `work_dir` 下各竞赛日子目录中的题面合订为一份
+///
+/// 每个子目录是一份完整的临时目录，其中的 main.typ 读取同一目录下的 data.json 与题目文件。
+pub(crate) fn compile_booklet(
+    work_dir: &Path,
+    days: &[String],
+    output_filename: &str,
+) -> Result<()> {
+    let main = days
+        .iter()
+        .map(|day| format!("#include \"{}/main.typ\"", day))
+        .collect::<Vec<_>>()
+        .join("\n#pagebreak(weak: true)\n");
+    fs::write(work_dir.join("main.typ"), main)?;
+    typst_compile(work_dir, "main.typ", output_filename)
+}
 
-            // 遍历 day_config.compile 中的语言配置来生成对应的提交文件名
-            for lang_key in day_config.compile.keys() {
-                submit_filenames.push(format!("{}.{}", problem_config.name, lang_key));
-            }
+/// 在 `work_dir` 下编译 Typst 文件，字体从 `work_dir` 下的 fonts 目录与系统中加载
+///
+/// 内置的 Typst 版本较旧且不支持导入包，编译失败时若 PATH 中有 typst 命令则改用其重新编译。
+#[cfg(feature = "embedded-typst")]
+pub(crate) fn typst_compile(work_dir: &Path, input: &str, output_filename: &str) -> Result<()> {
+    match embedded::compile(work_dir, input, output_filename) {
+        Ok(()) => Ok(()),
+        Err(e) if typst_command_available() => {
+            debug!("{:#}", e);
+            warn!("内置的 Typst 编译失败，改用 PATH 中的 typst 命令重新编译");
+            cli_compile(work_dir, input, output_filename)
+        }
+        Err(e) => Err(e),
+    }
+}
 
-            let point_equal = if problem_config.data.is_empty() {
-                // 如果没有测试数据，默认为"是"
-                "是".to_string()
-            } else {
-                // 获取第一个测试点的分数
-                let first_score = problem_config.data[0].score;
-                // 检查所有测试点的分数是否都等于第一个测试点的分数
-                let all_equal = problem_config
-                    .data
-                    .iter()
-                    .all(|data_item| data_item.score == first_score);
-
-                if all_equal {
-                    "是".to_string()
-                } else {
-                    "否".to_string()
-                }
-            };
+/// 在 `work_dir` 下编译 Typst 文件，字体从 `work_dir` 下的 fonts 目录与系统中加载
+#[cfg(not(feature = "embedded-typst"))]
+pub(crate) fn typst_compile(work_dir: &Path, input: &str, output_filename: &str) -> Result<()> {
+    cli_compile(work_dir, input, output_filename)
+}
 
-            let problem = Problem {
-                name: problem_config.name.clone(),
-                title: problem_config.title.clone(),
-                dir: problem_config.name.clone(), // 假设目录名就是问题名
-                exec: problem_config.name.clone(), // 默认值，你可能需要从配置文件读取
-                input: problem_config.name.clone() + ".in",
-                output: problem_config.name.clone() + ".out",
-                problem_type: match problem_config.problem_type {
-                    ProblemType::Program => "传统型",
-                    ProblemType::Output => "提交答案型",
-                    ProblemType::Interactive => "交互型",
-                }
-                .to_string(),
-                time_limit: format!("{:.1} 秒", problem_config.time_limit),
-                memory_limit: format!("{:.0}", problem_config.memory_limit),
-                testcase: problem_config.data.len().to_string(),
-                point_equal,
-                submit_filename: submit_filenames,
-            };
-            problems.push(problem);
-        }
+/// 使用 PATH 中的 typst 命令编译
+fn cli_compile(work_dir: &Path, input: &str, output_filename: &str) -> Result<()> {
+    let output = Command::new("typst")
+        .arg("compile")
+        .arg("--font-path=fonts")
+        .arg(input)
+        .arg(output_filename)
+        .current_dir(work_dir)
+        .output()?;
+    if output.status.success() {
+        info!("Typst 编译成功: {}", output_filename);
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Typst 编译失败:\n{}", stderr.trim_end())
+    }
+}
 
-        // 构建支持的语言列表
-        let context = crate::context::get_context();
-        let mut support_languages = Vec::new();
+/// 在题面源文件中查找与生成的 Typst 代码行对应的行号
+///
+/// 生成的代码与源文件没有逐行的对应关系，这里取该行中最长的一段文字在源文件中查找。
+fn locate_source_line(generated: &str, source: &str) -> Option<usize> {
+    let needle = generated
+        .split(|c: char| !c.is_alphanumeric() && c != ' ')
+        .map(str::trim)
+        .max_by_key(|part| part.chars().count())
+        .filter(|part| part.chars().count() >= 2)?;
+    source
+        .lines()
+        .position(|line| line.contains(needle))
+        .map(|idx| idx + 1)
+}
+impl TypstCompiler {
+    /// 将编译错误中 problem-N.typ 的位置对应回题面或题解的源文件
+    fn map_diagnostics(
+        &self,
+        error: anyhow::Error,
+        problems: &[(&Document, &ProblemConfig)],
+    ) -> anyhow::Error {
+        let re = Regex::new(r"problem-(\d+)\.typ:(\d+):(\d+)").unwrap();
+        let message = error.to_string();
 
-        for (lang_key, compile_options) in &day_config.compile {
-            // 从context中查找对应的语言配置来获取语言名称
-            let language_name = if let Some(lang_config) = context.languages.get(lang_key) {
-                lang_config.language.clone()
-            } else {
-                // 如果context中没有对应的语言配置，使用键名作为语言名称
-                error!("在语言配置中未找到 {}", lang_key);
-                bail!("在语言配置中未找到 {}", lang_key);
+        let mut locations = Vec::new();
+        for caps in re.captures_iter(&message) {
+            let (Ok(index), Ok(line)) = (caps[1].parse::<usize>(), caps[2].parse::<usize>()) else {
+                continue;
             };
-
-            let language = SupportLanguage {
-                name: language_name,
-                compile_options: compile_options.clone(),
+            let Some((_, problem)) = problems.get(index) else {
+                continue;
+            };
+            let statement_path = localized_file(
+                &problem.path,
+                self.options.source_stem,
+                self.options.language.as_deref(),
+            );
+            let generated = fs::read_to_string(self.tmp_dir.join(format!("problem-{}.typ", index)))
+                .ok()
+                .and_then(|content| {
+                    let idx = line.checked_sub(1)?;
+                    content.lines().nth(idx).map(str::to_string)
+                });
+            let source_line = match (generated, fs::read_to_string(&statement_path)) {
+                (Some(generated), Ok(source)) => locate_source_line(&generated, &source),
+                _ => None,
             };
-            support_languages.push(language);
+            let location = match source_line {
+                Some(source_line) => format!(
+                    "{}:{} (problem-{}.typ:{})",
+                    statement_path.display(),
+                    source_line,
+                    index,
+                    line
+                ),
+                None => format!(
+                    "{} (problem-{}.typ:{})",
+                    statement_path.display(),
+                    index,
+                    line
+                ),
+            };
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
         }
 
-        // 创建日期信息
-        let date = DateInfo {
-            start: day_config.start_time,
-            end: day_config.end_time,
-        };
+        if locations.is_empty() {
+            error
+        } else {
+            anyhow!("{}\n对应题面位置:\n  {}", message, locations.join("\n  "))
+        }
+    }
 
-        // 从ContestConfig和ContestDayConfig中获取覆盖值
-        let use_pretest = day_config
-            .use_pretest
-            .or(self.contest_config.use_pretest)
-            .unwrap_or(self.manifest.use_pretest);
-        let noi_style = day_config
-            .noi_style
-            .or(self.contest_config.noi_style)
-            .unwrap_or(self.manifest.noi_style);
-        let file_io = day_config
-            .file_io
-            .or(self.contest_config.file_io)
-            .unwrap_or(self.manifest.file_io);
-        let data_json = DataJson {
-            title: self.contest_config.title.clone(),
-            subtitle: self.contest_config.short_title.clone(),
-            dayname: day_config.title.clone(),
-            date,
-            use_pretest,
-            noi_style,
-            file_io,
-            support_languages,
-            problems,
+    /// 只包含 `problems` 与 `parts` 指定部分的一份文档，保存到 output 目录
+    fn compile_document(
+        &self,
+        problems: &[(&Document, &ProblemConfig)],
+        parts: DocumentParts,
+        output_name: &str,
+    ) -> Result<PathBuf> {
+        self.write_sources(problems, parts)?;
+        let output_filename = format!("output/{}", output_name);
+        typst_compile(&self.tmp_dir, "main.typ", &output_filename)
+            .map_err(|e| self.map_diagnostics(e, problems))?;
+        Ok(self.tmp_dir.join(output_filename))
+    }
+
+    /// 生成 data.json 与各题目的 Typst 文件
+    fn write_sources(
+        &self,
+        problems: &[(&Document, &ProblemConfig)],
+        parts: DocumentParts,
+    ) -> Result<()> {
+        let day_config = ContestDayConfig {
+            subconfig: self
+                .day_config
+                .subconfig
+                .iter()
+                .filter(|(_, config)| problems.iter().any(|(_, p)| p.name == config.name))
+                .map(|(key, config)| (key.clone(), config.clone()))
+                .collect(),
+            ..self.day_config.clone()
         };
+        self.generate_conf(&day_config, &self.tmp_dir, parts)?;
+        for (index, (ast, config)) in problems.iter().enumerate() {
+            self.convert_ast(config, &self.tmp_dir, ast, index)?;
+        }
+        Ok(())
+    }
 
+    fn generate_conf(
+        &self,
+        day_config: &ContestDayConfig,
+        tmp_dir: &Path,
+        parts: DocumentParts,
+    ) -> Result<()> {
+        let mut data_json = build_data_json(
+            &self.contest_config,
+            day_config,
+            &self.manifest,
+            &self.options,
+        )?;
+        data_json.parts = parts;
         let data_json_str = serde_json::to_string_pretty(&data_json)?;
         fs::write(tmp_dir.join("data.json"), data_json_str)?;
         info!("生成 data.json");
@@ -0,0 +1,202 @@
+use crate::prelude::*;
+use chrono::{Datelike, FixedOffset, Local, Utc};
+use comemo::Prehashed;
+use std::sync::{Mutex, OnceLock};
+use typst::diag::{FileError, FileResult, Severity, SourceDiagnostic};
+use typst::eval::Tracer;
+use typst::foundations::{Bytes, Datetime, Smart};
+use typst::syntax::{FileId, Source, VirtualPath};
+use typst::text::{Font, FontBook, FontInfo};
+use typst::{Library, World};
+
+/// 以工作目录为根目录的 Typst 编译环境
+struct TuackWorld {
+    root: PathBuf,
+    main: FileId,
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    fonts: Vec<FontSlot>,
+    sources: Mutex<HashMap<FileId, Source>>,
+}
+
+/// 一个字体，字体文件在第一次使用时才读取
+struct FontSlot {
+    path: Option<PathBuf>,
+    index: u32,
+    font: OnceLock<Option<Font>>,
+}
+
+impl FontSlot {
+    fn get(&self) -> Option<Font> {
+        self.font
+            .get_or_init(|| {
+                let data = fs::read(self.path.as_ref()?).ok()?;
+                Font::new(Bytes::from(data), self.index)
+            })
+            .clone()
+    }
+}
+
+/// 按与 typst 命令行相同的顺序查找字体：工作目录下的 fonts 目录、系统字体、Typst 内置的字体
+fn search_fonts(work_dir: &Path) -> (FontBook, Vec<FontSlot>) {
+    let mut db = fontdb::Database::new();
+    db.load_fonts_dir(work_dir.join("fonts"));
+    db.load_system_fonts();
+
+    let mut book = FontBook::new();
+    let mut fonts = Vec::new();
+    for face in db.faces() {
+        let path = match &face.source {
+            fontdb::Source::File(path) | fontdb::Source::SharedFile(path, _) => path,
+            fontdb::Source::Binary(_) => continue,
+        };
+        if let Some(info) = db.with_face_data(face.id, FontInfo::new).flatten() {
+            book.push(info);
+            fonts.push(FontSlot {
+                path: Some(path.clone()),
+                index: face.index,
+                font: OnceLock::new(),
+            });
+        }
+    }
+
+    for data in typst_assets::fonts() {
+        let buffer = Bytes::from_static(data);
+        for (index, info) in FontInfo::iter(data).enumerate() {
+            book.push(info);
+            fonts.push(FontSlot {
+                path: None,
+                index: index as u32,
+                font: OnceLock::from(Font::new(buffer.clone(), index as u32)),
+            });
+        }
+    }
+
+    (book, fonts)
+}
+
+impl TuackWorld {
+    fn new(work_dir: &Path, input: &str) -> Result<Self> {
+        let (book, fonts) = search_fonts(work_dir);
+        debug!("找到了 {} 个字体", fonts.len());
+
+        Ok(TuackWorld {
+            root: work_dir.to_path_buf(),
+            main: FileId::new(None, VirtualPath::new(input)),
+            library: Prehashed::new(Library::default()),
+            book: Prehashed::new(book),
+            fonts,
+            sources: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn read(&self, id: FileId) -> FileResult<Vec<u8>> {
+        if id.package().is_some() {
+            return Err(FileError::Other(Some(
+                "内置的 Typst 不支持导入包，请安装 typst 命令行工具".into(),
+            )));
+        }
+        let path = id
+            .vpath()
+            .resolve(&self.root)
+            .ok_or(FileError::AccessDenied)?;
+        fs::read(&path).map_err(|e| FileError::from_io(e, &path))
+    }
+}
+
+impl World for TuackWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.source(self.main)
+            .unwrap_or_else(|_| Source::new(self.main, String::new()))
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        let mut sources = self.sources.lock().unwrap();
+        if let Some(source) = sources.get(&id) {
+            return Ok(source.clone());
+        }
+        let text = String::from_utf8(self.read(id)?).map_err(|_| FileError::InvalidUtf8)?;
+        let source = Source::new(id, text);
+        sources.insert(id, source.clone());
+        Ok(source)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.read(id).map(Bytes::from)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index)?.get()
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        let date = match offset {
+            None => Local::now().date_naive(),
+            Some(hours) => Utc::now()
+                .with_timezone(&FixedOffset::east_opt((hours * 3600) as i32)?)
+                .date_naive(),
+        };
+        Datetime::from_ymd(date.year(), date.month() as u8, date.day() as u8)
+    }
+}
+
+/// 将诊断信息格式化为 `文件:行:列: 级别: 信息`，与 `typst compile --diagnostic-format short` 一致
+fn format_diagnostic(world: &TuackWorld, diagnostic: &SourceDiagnostic) -> String {
+    let level = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let location = diagnostic.span.id().and_then(|id| {
+        let source = world.source(id).ok()?;
+        let start = source.range(diagnostic.span)?.start;
+        Some(format!(
+            "{}:{}:{}",
+            id.vpath().as_rootless_path().display(),
+            source.byte_to_line(start)? + 1,
+            source.byte_to_column(start)? + 1
+        ))
+    });
+    let mut message = match location {
+        Some(location) => format!("{}: {}: {}", location, level, diagnostic.message),
+        None => format!("{}: {}", level, diagnostic.message),
+    };
+    for hint in &diagnostic.hints {
+        message.push_str(&format!("\n  = hint: {}", hint));
+    }
+    message
+}
+
+/// 在进程内编译 Typst 文件，参数与 [`super::typst_compile`] 相同
+pub fn compile(work_dir: &Path, input: &str, output_filename: &str) -> Result<()> {
+    let world = TuackWorld::new(work_dir, input)?;
+    let mut tracer = Tracer::new();
+    let result = typst::compile(&world, &mut tracer);
+
+    for warning in tracer.warnings() {
+        warn!("{}", format_diagnostic(&world, &warning));
+    }
+
+    match result {
+        Ok(document) => {
+            let pdf = typst_pdf::pdf(&document, Smart::Auto, world.today(None));
+            fs::write(work_dir.join(output_filename), pdf)?;
+            info!("Typst 编译成功: {}", output_filename);
+            Ok(())
+        }
+        Err(errors) => {
+            let messages: Vec<String> = errors
+                .iter()
+                .map(|error| format_diagnostic(&world, error))
+                .collect();
+            bail!("Typst 编译失败:\n{}", messages.join("\n"))
+        }
+    }
+}
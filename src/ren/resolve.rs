@@ -0,0 +1,102 @@
+use crate::context;
+use crate::prelude::*;
+use crate::utils::filesystem::copy_dir_recursive;
+use serde_json::{Map, Value};
+
+/// 查找模板的位置：比赛目录下的 templates 优先，其次是各资源目录
+fn template_search_dirs(config: &ContestConfig) -> Vec<PathBuf> {
+    let mut dirs = vec![config.path.join("templates")];
+    dirs.extend(
+        context::get_context()
+            .assets_dirs
+            .iter()
+            .map(|dir| dir.join("templates")),
+    );
+    dirs
+}
+
+/// 查找名为 `target` 的模板目录，跳过 `exclude` 中的目录
+fn find_in(config: &ContestConfig, target: &str, exclude: &[PathBuf]) -> Option<PathBuf> {
+    template_search_dirs(config)
+        .into_iter()
+        .map(|dir| dir.join(target))
+        .find(|dir| dir.is_dir() && !exclude.contains(dir))
+}
+
+/// 是否存在名为 `target` 的模板
+pub(crate) fn template_exists(config: &ContestConfig, target: &str) -> bool {
+    find_in(config, target, &[]).is_some()
+}
+
+/// 查找名为 `target` 的模板目录
+pub(crate) fn find_template_dir(config: &ContestConfig, target: &str) -> Result<PathBuf> {
+    match find_in(config, target, &[]) {
+        Some(dir) => {
+            info!("找到模板目录: {}", dir.to_string_lossy());
+            Ok(dir)
+        }
+        None => {
+            error!("没有找到模板 {}", target);
+            bail!("没有找到模板 {}", target);
+        }
+    }
+}
+
+/// 读取模板目录中的清单文件，不存在时返回空对象
+fn read_manifest(dir: &Path) -> Result<Map<String, Value>> {
+    let manifest_file = dir.join("manifest.json");
+    if !manifest_file.exists() {
+        return Ok(Map::new());
+    }
+    let content = fs::read_to_string(&manifest_file)?;
+    match serde_json::from_str(&content)? {
+        Value::Object(map) => Ok(map),
+        _ => bail!("清单文件不是 JSON 对象: {}", manifest_file.display()),
+    }
+}
+
+/// 模板及其通过清单中 `extends` 继承的各层模板目录，被继承的模板在前
+///
+/// 查找被继承的模板时会跳过已经找到的各层，因此比赛目录下的模板可以与继承的系统模板同名。
+pub(crate) fn template_layers(config: &ContestConfig, target: &str) -> Result<Vec<PathBuf>> {
+    let mut layers = vec![find_template_dir(config, target)?];
+    loop {
+        let current = layers.last().unwrap();
+        let manifest = read_manifest(current)?;
+        let Some(parent) = manifest.get("extends").and_then(|value| value.as_str()) else {
+            break;
+        };
+        let Some(parent_dir) = find_in(config, parent, &layers) else {
+            error!("没有找到模板 {} 继承的模板 {}", current.display(), parent);
+            bail!("没有找到模板 {} 继承的模板 {}", current.display(), parent);
+        };
+        info!("模板 {} 继承 {}", current.display(), parent_dir.display());
+        layers.push(parent_dir);
+    }
+    layers.reverse();
+    Ok(layers)
+}
+
+/// 将各层模板依次复制到 `dst`，后面的文件覆盖前面的同名文件
+///
+/// 清单文件按字段合并，子模板中的字段覆盖被继承模板中的字段。
+pub(crate) fn assemble_template(layers: &[PathBuf], dst: &Path) -> Result<()> {
+    if dst.exists() {
+        fs::remove_dir_all(dst)?;
+    }
+    fs::create_dir_all(dst)?;
+
+    let mut manifest = Map::new();
+    for layer in layers {
+        copy_dir_recursive(layer, dst)?;
+        manifest.extend(read_manifest(layer)?);
+    }
+    manifest.remove("extends");
+    fs::write(
+        dst.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    info!("合并模板到: {}", dst.display());
+
+    Ok(())
+}
@@ -1,22 +1,33 @@
 use crate::config::TemplateManifest;
 use crate::prelude::*;
+use crate::ren::RenderOptions;
+use crate::ren::locale::{self, Locale};
 use anyhow::Result;
 use minijinja::Value;
-use minijinja::{Environment, context};
+use minijinja::value::Kwargs;
+use minijinja::{Environment, UndefinedBehavior, context};
 
-fn input_file(problem: &ProblemConfig, file_io: bool) -> Result<String, minijinja::Error> {
+fn input_file(
+    problem: &ProblemConfig,
+    file_io: bool,
+    locale: &Locale,
+) -> Result<String, minijinja::Error> {
     Ok(if file_io {
-        format!("从文件 _{}.in_ 中读入数据。", problem.name)
+        locale.input_file.replace("{name}", &problem.name)
     } else {
-        "从标准输入读入数据。".to_string()
+        locale.input_stdin.to_string()
     })
 }
 
-fn output_file(problem: &ProblemConfig, file_io: bool) -> Result<String, minijinja::Error> {
+fn output_file(
+    problem: &ProblemConfig,
+    file_io: bool,
+    locale: &Locale,
+) -> Result<String, minijinja::Error> {
     Ok(if file_io {
-        format!("输出到文件 _{}.out_ 中。", problem.name)
+        locale.output_file.replace("{name}", &problem.name)
     } else {
-        "输出到标准输出。".to_string()
+        locale.output_stdout.to_string()
     })
 }
 
@@ -25,6 +36,7 @@ fn handle_sample(
     sample_id: u32,
     problem: &ProblemConfig,
     base_path: &Path,
+    locale: &Locale,
 ) -> Result<String, minijinja::Error> {
     debug!("处理 sample 函数: {}", sample_id);
 
@@ -41,7 +53,10 @@ fn handle_sample(
     let mut md = String::new();
 
     // 输入部分
-    md.push_str(&format!("## 样例 {} 输入\n\n", sample_id));
+    md.push_str(&format!(
+        "## {}\n\n",
+        locale.sample_input.replace("{id}", &sample_id.to_string())
+    ));
 
     if let Some(input_file) = &sample_item.input.get() {
         let input_path = base_path.join("sample").join(input_file);
@@ -68,7 +83,10 @@ fn handle_sample(
     }
 
     // 输出部分
-    md.push_str(&format!("## 样例 {} 输出\n\n", sample_id));
+    md.push_str(&format!(
+        "## {}\n\n",
+        locale.sample_output.replace("{id}", &sample_id.to_string())
+    ));
 
     if let Some(output_file) = &sample_item.output.get() {
         let output_path = base_path.join("sample").join(output_file);
@@ -99,7 +117,11 @@ fn handle_sample(
 }
 
 /// 处理 sample_file 函数
-fn handle_sample_file(sample_id: u32, problem: &ProblemConfig) -> Result<String, minijinja::Error> {
+fn handle_sample_file(
+    sample_id: u32,
+    problem: &ProblemConfig,
+    locale: &Locale,
+) -> Result<String, minijinja::Error> {
     debug!("处理 sample_file 函数: {}", sample_id);
 
     // 检查样本是否存在
@@ -108,15 +130,222 @@ fn handle_sample_file(sample_id: u32, problem: &ProblemConfig) -> Result<String,
     }
 
     // 直接生成Markdown文本
-    let text = format!(
-        "见选手目录下的 _{0}/{0}{1}.in_ 与 _{0}/{0}{1}.ans_。",
-        problem.name, sample_id
-    );
+    let text = locale
+        .sample_file
+        .replace("{name}", &problem.name)
+        .replace("{id}", &sample_id.to_string());
 
     info!("生成文件引用: sample_file({}) -> {}", sample_id, text);
     Ok(text)
 }
 
+/// 根据扩展名确定代码块的语言
+fn code_language(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("cpp" | "cc" | "cxx" | "hpp" | "h") => "cpp",
+        Some("c") => "c",
+        Some("py") => "python",
+        Some("java") => "java",
+        Some("rs") => "rust",
+        Some("pas") => "pascal",
+        _ => "txt",
+    }
+}
+
+/// 将源代码文件转换为 Markdown 代码块
+fn code_block(path: &Path) -> Result<String, minijinja::Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => {
+            let mut md = format!("```{}\n", code_language(path));
+            md.push_str(&content);
+            if !content.ends_with('\n') {
+                md.push('\n');
+            }
+            md.push_str("```\n");
+            Ok(md)
+        }
+        Err(e) => {
+            error!("读取代码文件失败: {:?} -> {}", path, e);
+            Ok(format!("*读取失败：{}*", path.display()))
+        }
+    }
+}
+
+/// 处理 solution.code 函数，引用 tests 中的程序，默认为 std
+fn handle_code(name: Option<&str>, problem: &ProblemConfig) -> Result<String, minijinja::Error> {
+    let name = name.unwrap_or("std");
+    debug!("处理 solution.code 函数: {}", name);
+
+    let Some(test) = problem.tests.get(name) else {
+        warn!("未找到程序: {}", name);
+        return Ok(format!("**错误：未找到程序 {}**", name));
+    };
+    let path = problem.path.join(&test.path);
+    if path.is_dir() {
+        warn!("程序 {} 是目录，无法引用", name);
+        return Ok(format!("**错误：程序 {} 是目录**", name));
+    }
+    code_block(&path)
+}
+
+/// 处理 solution.file 函数，引用题解目录下的文件
+fn handle_solution_file(file: &str, problem: &ProblemConfig) -> Result<String, minijinja::Error> {
+    debug!("处理 solution.file 函数: {}", file);
+
+    let path = problem.path.join("solution").join(file);
+    if !path.exists() {
+        warn!("未找到题解文件: {}", path.display());
+        return Ok(format!("*文件不存在：{}*", path.display()));
+    }
+    code_block(&path)
+}
+
+/// 处理 solution.subtasks 函数，生成子任务的测试点与分值表
+fn handle_subtasks(problem: &ProblemConfig, locale: &Locale) -> Result<String, minijinja::Error> {
+    if problem.subtasks.is_empty() {
+        warn!("题目 {} 没有子任务", problem.name);
+        return Ok(String::new());
+    }
+
+    let mut md = format!(
+        "| {} | {} | {} |\n| :-: | :-: | :-: |\n",
+        locale.subtask, locale.testcase, locale.score
+    );
+    for (id, subtask) in &problem.subtasks {
+        let mut ids: Vec<i32> = subtask.items.iter().map(|item| item.id as i32).collect();
+        ids.sort();
+        md.push_str(&format!(
+            "| {} | {} | {} |\n",
+            id,
+            cases(ids)?,
+            subtask.max_score
+        ));
+    }
+    Ok(md)
+}
+
+/// 数据范围表中的一行：测试点编号、参数与分值
+struct DataRow {
+    ids: Vec<i32>,
+    args: HashMap<String, i64>,
+    score: u32,
+}
+
+/// 将测试点按子任务与参数分组，捆绑的子任务合为一行，其余相邻且参数与分值相同的测试点合为一行
+fn data_rows(problem: &ProblemConfig) -> Vec<DataRow> {
+    let mut data: Vec<_> = problem.data.iter().collect();
+    data.sort_by_key(|item| item.id);
+
+    let mut rows: Vec<DataRow> = Vec::new();
+    let mut bundled: HashMap<u32, usize> = HashMap::new();
+    for item in data {
+        let subtask = problem
+            .subtasks
+            .get(&item.subtask)
+            .filter(|subtask| !matches!(subtask.policy, ScorePolicy::Sum));
+
+        if let Some(subtask) = subtask {
+            // 捆绑的子任务取各参数的最大值
+            if let Some(&idx) = bundled.get(&item.subtask) {
+                let row = &mut rows[idx];
+                row.ids.push(item.id as i32);
+                for (key, value) in &item.args {
+                    let max = row.args.entry(key.clone()).or_insert(*value);
+                    *max = (*max).max(*value);
+                }
+            } else {
+                bundled.insert(item.subtask, rows.len());
+                rows.push(DataRow {
+                    ids: vec![item.id as i32],
+                    args: item.args.clone(),
+                    score: subtask.max_score,
+                });
+            }
+            continue;
+        }
+
+        let last_bundled = bundled.values().any(|&idx| idx + 1 == rows.len());
+        match rows.last_mut() {
+            Some(row) if !last_bundled && row.args == item.args && row.score == item.score => {
+                row.ids.push(item.id as i32);
+            }
+            _ => rows.push(DataRow {
+                ids: vec![item.id as i32],
+                args: item.args.clone(),
+                score: item.score,
+            }),
+        }
+    }
+    rows
+}
+
+/// 处理 data_table 函数，根据测试点配置生成数据范围表
+///
+/// `columns` 指定作为约束列的参数及顺序，默认为所有参数按名称排序；
+/// `special` 将参数名映射为特殊性质的描述，参数非零的行会列出该性质。
+fn data_table(
+    problem: &ProblemConfig,
+    columns: Option<Vec<String>>,
+    special: Option<BTreeMap<String, String>>,
+    locale: &Locale,
+) -> Result<String, minijinja::Error> {
+    if problem.data.is_empty() {
+        warn!("题目 {} 没有测试点", problem.name);
+        return Ok(String::new());
+    }
+
+    let special = special.unwrap_or_default();
+    let columns = columns.unwrap_or_else(|| {
+        let mut keys: Vec<String> = problem
+            .data
+            .iter()
+            .flat_map(|item| item.args.keys())
+            .filter(|key| !special.contains_key(*key))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    });
+
+    let mut header = vec![locale.testcase_id.to_string()];
+    header.extend(columns.iter().map(|key| format!("${}$", key)));
+    if !special.is_empty() {
+        header.push(locale.special.to_string());
+    }
+    header.push(locale.score.to_string());
+
+    let mut md = format!("| {} |\n|", header.join(" | "));
+    md.push_str(&" :-: |".repeat(header.len()));
+    md.push('\n');
+
+    for row in data_rows(problem) {
+        let mut cells = vec![cases(row.ids)?];
+        for key in &columns {
+            cells.push(match row.args.get(key) {
+                Some(value) => format!("$\\le {}$", hn(*value as f64, None)?),
+                None => "-".to_string(),
+            });
+        }
+        if !special.is_empty() {
+            let properties: Vec<&str> = special
+                .iter()
+                .filter(|(key, _)| row.args.get(*key).is_some_and(|value| *value != 0))
+                .map(|(_, text)| text.as_str())
+                .collect();
+            cells.push(if properties.is_empty() {
+                locale.none.to_string()
+            } else {
+                properties.join("，")
+            });
+        }
+        cells.push(row.score.to_string());
+        md.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+
+    Ok(md)
+}
+
 /// 计算一个数的以10为底的对数的整数部分
 ///
 /// # 参数
@@ -310,9 +539,39 @@ pub fn render_template(
     contest: &ContestConfig,
     base_path: PathBuf,
     manifest: TemplateManifest,
+    options: &RenderOptions,
 ) -> Result<String> {
-    // 创建环境
     let env = Environment::new();
+    let ctx = template_context(problem, day, contest, base_path, manifest, options);
+    Ok(env.render_str(template, ctx)?)
+}
+
+/// 与 [`render_template`] 相同，但使用未定义的变量或属性时报错，用于检查题面
+pub fn render_template_strict(
+    template: &str,
+    problem: &ProblemConfig,
+    day: &ContestDayConfig,
+    contest: &ContestConfig,
+    base_path: PathBuf,
+    manifest: TemplateManifest,
+    options: &RenderOptions,
+) -> Result<String> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    let ctx = template_context(problem, day, contest, base_path, manifest, options);
+    Ok(env.render_str(template, ctx)?)
+}
+
+/// 题面模板中可以使用的变量与函数
+fn template_context(
+    problem: &ProblemConfig,
+    day: &ContestDayConfig,
+    contest: &ContestConfig,
+    base_path: PathBuf,
+    manifest: TemplateManifest,
+    options: &RenderOptions,
+) -> Value {
+    let locale = locale::get(options.language.as_deref());
 
     let sample = HashMap::from([
         (
@@ -321,7 +580,7 @@ pub fn render_template(
                 let problem = problem.clone();
                 let base_path = base_path.clone();
                 move |sample_id: u32| -> Result<String, minijinja::Error> {
-                    handle_sample(sample_id, &problem, &base_path)
+                    handle_sample(sample_id, &problem, &base_path, locale)
                 }
             }),
         ),
@@ -330,7 +589,7 @@ pub fn render_template(
             Value::from_function({
                 let problem = problem.clone();
                 move |sample_id: u32| -> Result<String, minijinja::Error> {
-                    handle_sample_file(sample_id, &problem)
+                    handle_sample_file(sample_id, &problem, locale)
                 }
             }),
         ),
@@ -365,7 +624,11 @@ pub fn render_template(
             Value::from_function({
                 let problem = problem.clone();
                 move || -> Result<String, minijinja::Error> {
-                    input_file(&problem, problem.file_io.unwrap_or(manifest.file_io))
+                    input_file(
+                        &problem,
+                        problem.file_io.unwrap_or(manifest.file_io),
+                        locale,
+                    )
                 }
             }),
         ),
@@ -374,24 +637,72 @@ pub fn render_template(
             Value::from_function({
                 let problem = problem.clone();
                 move || -> Result<String, minijinja::Error> {
-                    output_file(&problem, problem.file_io.unwrap_or(manifest.file_io))
+                    output_file(
+                        &problem,
+                        problem.file_io.unwrap_or(manifest.file_io),
+                        locale,
+                    )
                 }
             }),
         ),
     ]);
 
-    // 创建上下文
-    let ctx = context! {
+    let solution = HashMap::from([
+        (
+            "code",
+            Value::from_function({
+                let problem = problem.clone();
+                move |name: Option<&str>| -> Result<String, minijinja::Error> {
+                    handle_code(name, &problem)
+                }
+            }),
+        ),
+        (
+            "file",
+            Value::from_function({
+                let problem = problem.clone();
+                move |file: &str| -> Result<String, minijinja::Error> {
+                    handle_solution_file(file, &problem)
+                }
+            }),
+        ),
+        (
+            "subtasks",
+            Value::from_function({
+                let problem = problem.clone();
+                move || -> Result<String, minijinja::Error> { handle_subtasks(&problem, locale) }
+            }),
+        ),
+    ]);
+
+    let data_table = Value::from_function({
+        let problem = problem.clone();
+        move |kwargs: Kwargs| -> Result<String, minijinja::Error> {
+            let columns = kwargs.get("columns")?;
+            let special = match kwargs.get::<Option<Value>>("special")? {
+                Some(value) => {
+                    let mut map = BTreeMap::new();
+                    for key in value.try_iter()? {
+                        map.insert(key.to_string(), value.get_item(&key)?.to_string());
+                    }
+                    Some(map)
+                }
+                None => None,
+            };
+            kwargs.assert_all_used()?;
+            self::data_table(&problem, columns, special, locale)
+        }
+    });
+
+    context! {
         problem => problem,
         day => day,
         contest => contest,
         sample => sample,
         tools => tools,
         statement => statement,
-        s => statement
-    };
-
-    // 渲染模板
-    let result = env.render_str(template, ctx)?;
-    Ok(result)
+        s => statement,
+        solution => solution,
+        data_table => data_table
+    }
 }
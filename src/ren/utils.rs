@@ -1,5 +1,7 @@
 use crate::prelude::*;
-use markdown_ppp::ast_transform::Transform;
+use markdown_ppp::ast::{Block, Document, Inline};
+use markdown_ppp::ast_transform::{Transform, Transformer};
+use markdown_ppp::parser::{MarkdownParserState, parse_markdown};
 use sha2::{Digest, Sha256};
 use std::ffi::OsStr;
 
@@ -49,10 +51,18 @@ pub fn process_images_with_unique_ids(
 }
 
 /// 修改图片路径，将相对路径替换为唯一ID路径
+///
+/// 只处理以图片目录名（如 `img/`）开头的路径，替换后的路径均位于 `img/` 下。
 pub fn process_image_urls(img_src_dir: &Path, ast: &mut markdown_ppp::ast::Document) {
     if img_src_dir.exists() && img_src_dir.is_dir() {
+        let dir_name = img_src_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("img");
+        let prefix = format!("{}/", dir_name);
+        let dot_prefix = format!("./{}/", dir_name);
         *ast = ast.clone().transform_image_urls(|url| {
-            if url.starts_with("./img/") || url.starts_with("img/") {
+            if url.starts_with(&dot_prefix) || url.starts_with(&prefix) {
                 let filename = Path::new(&url)
                     .file_name()
                     .and_then(|name| name.to_str())
@@ -88,11 +98,317 @@ pub fn process_image_urls(img_src_dir: &Path, ast: &mut markdown_ppp::ast::Docum
                 }
             } else {
                 warn!(
-                    "图片 url 不合法: {}, 不支持使用在 img/ 以外的图片, 可能会产生问题。",
-                    url
+                    "图片 url 不合法: {}, 不支持使用在 {} 以外的图片, 可能会产生问题。",
+                    url, prefix
                 );
                 url
             }
         });
     }
 }
+
+/// 公式的占位符，只含字母与数字，不会被各渲染器转义
+pub fn math_placeholder(idx: usize) -> String {
+    format!("TUACKMATH{}X", idx)
+}
+
+/// 文本按公式拆分后的一段
+enum MathSegment<'a> {
+    /// 普通文字
+    Text(&'a str),
+    /// 公式，`source` 包括两侧的 `$` 或 `$$`
+    Formula {
+        source: &'a str,
+        body: &'a str,
+        display: bool,
+    },
+    /// 转义的 `\$`
+    Dollar,
+    /// 没有闭合的 `$` 或 `$$`，`context` 为其后同一行的文字
+    Unclosed { delim: &'a str, context: &'a str },
+}
+
+/// 下一个字符的长度，`idx` 位于字符串末尾时为 0
+fn char_len(text: &str, idx: usize) -> usize {
+    text[idx..].chars().next().map_or(0, char::len_utf8)
+}
+
+/// 查找第一个未被反斜杠转义的 `delim`
+fn find_unescaped(text: &str, delim: &str) -> Option<usize> {
+    let mut idx = 0;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        if rest.starts_with('\\') {
+            idx += 1 + char_len(text, idx + 1);
+        } else if rest.starts_with(delim) {
+            return Some(idx);
+        } else {
+            idx += char_len(text, idx);
+        }
+    }
+    None
+}
+
+/// 按 `$`、`$$` 将文本拆分为文字与公式，行内公式不跨段落
+fn split_math(text: &str) -> Vec<MathSegment<'_>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut idx = 0;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        if rest.starts_with("\\$") {
+            segments.push(MathSegment::Text(&text[start..idx]));
+            segments.push(MathSegment::Dollar);
+            idx += 2;
+            start = idx;
+        } else if rest.starts_with('\\') {
+            idx += 1 + char_len(text, idx + 1);
+        } else if rest.starts_with('$') {
+            let (delim, display) = if rest.starts_with("$$") {
+                ("$$", true)
+            } else {
+                ("$", false)
+            };
+            segments.push(MathSegment::Text(&text[start..idx]));
+            let body_start = idx + delim.len();
+            let body = &text[body_start..];
+            let limit = if display {
+                body.len()
+            } else {
+                body.find("\n\n").unwrap_or(body.len())
+            };
+            match find_unescaped(&body[..limit], delim) {
+                Some(end) if !body[..end].trim().is_empty() => {
+                    let open = idx;
+                    idx = body_start + end + delim.len();
+                    segments.push(MathSegment::Formula {
+                        source: &text[open..idx],
+                        body: &body[..end],
+                        display,
+                    });
+                }
+                _ => {
+                    segments.push(MathSegment::Unclosed {
+                        delim: &text[idx..body_start],
+                        context: body.lines().next().unwrap_or(""),
+                    });
+                    idx = body_start;
+                }
+            }
+            start = idx;
+        } else {
+            idx += char_len(text, idx);
+        }
+    }
+    segments.push(MathSegment::Text(&text[start..]));
+    segments
+}
+
+/// 将文本中以 `$`、`$$` 包围的公式替换为占位符，公式及是否为行间公式保存到 `formulas`
+///
+/// `\$` 视为普通的 `$`。
+pub fn extract_math(text: &str, formulas: &mut Vec<(String, bool)>) -> String {
+    let mut result = String::new();
+    for segment in split_math(text) {
+        match segment {
+            MathSegment::Text(text) => result.push_str(text),
+            MathSegment::Formula { body, display, .. } => {
+                formulas.push((body.trim().to_string(), display));
+                result.push_str(&math_placeholder(formulas.len() - 1));
+            }
+            MathSegment::Dollar => result.push('$'),
+            MathSegment::Unclosed { delim, .. } => result.push_str(delim),
+        }
+    }
+    result
+}
+
+/// 将 Markdown 源文件中代码块、行内代码以外的部分依次交给 `f` 处理，代码原样保留
+fn map_outside_code(source: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut result = String::new();
+    let mut text = String::new();
+    let mut fence: Option<String> = None;
+
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = &fence {
+            result.push_str(line);
+            if trimmed.trim_end().starts_with(marker.as_str())
+                && trimmed
+                    .trim_end()
+                    .chars()
+                    .all(|c| c == marker.as_bytes()[0] as char)
+            {
+                fence = None;
+            }
+            continue;
+        }
+        // 列表项中的代码块缩进可以超过三个空格，因此不限制缩进
+        let marker: String = trimmed
+            .chars()
+            .take_while(|&c| c == '`' || c == '~')
+            .collect();
+        let is_fence =
+            marker.len() >= 3 && marker.chars().all(|c| c == marker.as_bytes()[0] as char);
+        if is_fence {
+            result.push_str(&map_outside_code_spans(&text, &mut f));
+            text.clear();
+            result.push_str(line);
+            fence = Some(marker);
+        } else {
+            text.push_str(line);
+        }
+    }
+    result.push_str(&map_outside_code_spans(&text, &mut f));
+    result
+}
+
+/// 将文本中行内代码以外的部分交给 `f` 处理
+fn map_outside_code_spans(text: &str, f: &mut impl FnMut(&str) -> String) -> String {
+    let mut result = String::new();
+    let mut start = 0;
+    let mut idx = 0;
+    while let Some(offset) = text[idx..].find('`') {
+        let open = idx + offset;
+        let run = text[open..].chars().take_while(|&c| c == '`').count();
+        let ticks = "`".repeat(run);
+        let mut search = open + run;
+        let close = loop {
+            match text[search..].find(&ticks) {
+                Some(offset) => {
+                    let pos = search + offset;
+                    let len = text[pos..].chars().take_while(|&c| c == '`').count();
+                    if len == run {
+                        break Some(pos);
+                    }
+                    search = pos + len;
+                }
+                None => break None,
+            }
+        };
+        match close {
+            Some(close) => {
+                result.push_str(&f(&text[start..open]));
+                result.push_str(&text[open..close + run]);
+                idx = close + run;
+                start = idx;
+            }
+            None => idx = open + run,
+        }
+    }
+    result.push_str(&f(&text[start..]));
+    result
+}
+
+/// 源文件中公式的占位符
+fn source_placeholder(idx: usize) -> String {
+    format!("TUACKSRC{}X", idx)
+}
+
+/// 源文件中 `\$` 的占位符
+const DOLLAR_PLACEHOLDER: &str = "TUACKDOLLARX";
+
+/// 解析题面，公式保持源文件中的原样
+///
+/// 公式在解析前替换为占位符，避免其中的反斜杠被当作转义、`*` 被当作强调，解析后再换回原文。
+pub fn parse_statement(content: &str) -> Result<Document> {
+    let mut formulas = Vec::new();
+    let protected = map_outside_code(content, |text| {
+        split_math(text)
+            .into_iter()
+            .map(|segment| match segment {
+                MathSegment::Text(text) => text.to_string(),
+                MathSegment::Formula { source, .. } => {
+                    formulas.push(source.to_string());
+                    source_placeholder(formulas.len() - 1)
+                }
+                MathSegment::Dollar => DOLLAR_PLACEHOLDER.to_string(),
+                MathSegment::Unclosed { delim, .. } => delim.to_string(),
+            })
+            .collect()
+    });
+
+    let ast =
+        parse_markdown(MarkdownParserState::new(), &protected).map_err(|e| anyhow!("{:?}", e))?;
+
+    Ok(ast.transform_with(RestorePlaceholders {
+        formulas: &formulas,
+    }))
+}
+
+/// 还原 [`parse_statement`] 中替换的占位符
+///
+/// 缩进代码块等未被 [`map_outside_code`] 识别的代码中也可能出现占位符，一并还原。
+struct RestorePlaceholders<'a> {
+    formulas: &'a [String],
+}
+
+impl RestorePlaceholders<'_> {
+    fn restore(&self, text: String) -> String {
+        if !text.contains("TUACK") {
+            return text;
+        }
+        let mut text = text.replace(DOLLAR_PLACEHOLDER, "\\$");
+        for (idx, formula) in self.formulas.iter().enumerate() {
+            text = text.replace(&source_placeholder(idx), formula);
+        }
+        text
+    }
+}
+
+impl Transformer for RestorePlaceholders<'_> {
+    fn transform_inline(&mut self, inline: Inline) -> Inline {
+        match inline {
+            Inline::Text(text) => Inline::Text(self.restore(text)),
+            Inline::Code(code) => Inline::Code(self.restore(code)),
+            other => self.walk_transform_inline(other),
+        }
+    }
+
+    fn transform_block(&mut self, block: Block) -> Block {
+        match block {
+            Block::CodeBlock(mut code) => {
+                code.literal = self.restore(code.literal);
+                Block::CodeBlock(code)
+            }
+            other => self.walk_transform_block(other),
+        }
+    }
+}
+
+/// 源文件中代码以外的公式，以及未闭合的 `$` 之后的文字
+pub fn source_math(source: &str) -> (Vec<(String, bool)>, Vec<String>) {
+    let mut formulas = Vec::new();
+    let mut unclosed = Vec::new();
+    map_outside_code(source, |text| {
+        for segment in split_math(text) {
+            match segment {
+                MathSegment::Formula { body, display, .. } => {
+                    formulas.push((body.trim().to_string(), display))
+                }
+                MathSegment::Unclosed { delim, context } => {
+                    unclosed.push(format!("{}{}", delim, context))
+                }
+                MathSegment::Text(_) | MathSegment::Dollar => {}
+            }
+        }
+        String::new()
+    });
+    (formulas, unclosed)
+}
+
+/// 行内元素中的文字
+pub fn inline_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(s) | Inline::Code(s) => text.push_str(s),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children) => text.push_str(&inline_text(children)),
+            Inline::Link(link) => text.push_str(&inline_text(&link.children)),
+            _ => {}
+        }
+    }
+    text
+}
@@ -0,0 +1,221 @@
+use super::{RenArgs, problems_in_scope, render, resolve};
+use crate::config::{CONFIG_FILE_NAME, load_config};
+use crate::prelude::*;
+use notify::{Event, RecursiveMode, Watcher, recommended_watcher};
+use std::sync::mpsc::{Receiver, channel};
+use std::time::Duration;
+
+/// 连续修改合并为一次渲染的等待时间
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 一次修改需要重新渲染的范围
+#[derive(Debug, Clone, PartialEq)]
+enum Scope {
+    All,
+    Day(String),
+}
+
+/// 需要监视的路径，目录不存在的子目录在其父目录中监视创建
+fn watch_paths(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+    template_layers: &[PathBuf],
+) -> Vec<(PathBuf, RecursiveMode)> {
+    let mut paths = vec![(config.path.clone(), RecursiveMode::NonRecursive)];
+    paths.extend(
+        template_layers
+            .iter()
+            .map(|dir| (dir.clone(), RecursiveMode::Recursive)),
+    );
+    for (day, problems) in problems_in_scope(config, current_location) {
+        paths.push((day.path.clone(), RecursiveMode::NonRecursive));
+        for prob in problems {
+            paths.push((prob.path.clone(), RecursiveMode::NonRecursive));
+            for subdir in [args.image_dir(), "sample"] {
+                let dir = prob.path.join(subdir);
+                if dir.is_dir() {
+                    paths.push((dir, RecursiveMode::Recursive));
+                }
+            }
+        }
+    }
+    paths
+}
+
+/// 是否为 `stem.md` 或某种语言的 `stem.<语言>.md`
+fn is_source(path: &Path, stem: &str) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix(stem))
+        .and_then(|rest| rest.strip_suffix(".md"))
+        .is_some_and(|lang| lang.is_empty() || lang.starts_with('.'))
+}
+
+/// 根据修改的文件确定重新渲染的范围，以及是否需要重新加载配置
+fn affected(
+    args: &RenArgs,
+    config: &ContestConfig,
+    current_location: &CurrentLocation,
+    template_layers: &[PathBuf],
+    path: &Path,
+) -> Option<(Scope, bool)> {
+    let is_config = path
+        .file_name()
+        .is_some_and(|name| name == CONFIG_FILE_NAME);
+
+    if template_layers.iter().any(|dir| path.starts_with(dir)) {
+        return Some((Scope::All, false));
+    }
+    if path == config.path.join(CONFIG_FILE_NAME) {
+        return Some((Scope::All, true));
+    }
+    if path.parent() == Some(config.path.as_path()) && is_source(path, "precaution") {
+        return Some((Scope::All, false));
+    }
+
+    for (day, problems) in problems_in_scope(config, current_location) {
+        if path == day.path.join(CONFIG_FILE_NAME) {
+            return Some((Scope::Day(day.name.clone()), true));
+        }
+        for prob in problems {
+            let in_problem = path.parent() == Some(prob.path.as_path())
+                && (is_config || is_source(path, args.source_stem()));
+            let in_assets = [args.image_dir(), "sample"]
+                .iter()
+                .any(|subdir| path.starts_with(prob.path.join(subdir)));
+            if in_problem || in_assets {
+                return Some((Scope::Day(day.name.clone()), is_config));
+            }
+        }
+    }
+
+    None
+}
+
+/// 等待下一批修改，合并短时间内的连续修改
+fn next_changes(rx: &Receiver<notify::Result<Event>>) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut event = rx.recv()?;
+    loop {
+        match event {
+            Ok(event) if !event.kind.is_access() => paths.extend(event.paths),
+            Ok(_) => {}
+            Err(e) => warn!("监视文件时出错: {}", e),
+        }
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(next) => event = next,
+            Err(_) => break,
+        }
+    }
+    Ok(paths)
+}
+
+/// 将监视的路径更新为 `paths`，只增删有变化的路径
+fn update_watches(
+    watcher: &mut impl Watcher,
+    watched: &mut Vec<(PathBuf, RecursiveMode)>,
+    paths: Vec<(PathBuf, RecursiveMode)>,
+) {
+    for (path, _) in watched.iter().filter(|entry| !paths.contains(entry)) {
+        if let Err(e) = watcher.unwatch(path) {
+            debug!("取消监视 {} 失败: {}", path.display(), e);
+        }
+    }
+    watched.retain(|entry| paths.contains(entry));
+    for (path, mode) in paths {
+        if watched.contains(&(path.clone(), mode)) {
+            continue;
+        }
+        match watcher.watch(&path, mode) {
+            Ok(()) => watched.push((path, mode)),
+            Err(e) => warn!("无法监视 {}: {}", path.display(), e),
+        }
+    }
+}
+
+pub fn main(
+    args: &RenArgs,
+    mut config: ContestConfig,
+    mut current_location: CurrentLocation,
+) -> Result<()> {
+    let mut template_layers = resolve::template_layers(&config, &args.target)?;
+
+    // 监视在各轮之间保持，渲染期间的修改留在通道中，下一轮再处理
+    let (tx, rx) = channel();
+    let mut watcher = recommended_watcher(tx)?;
+    let mut watched = Vec::new();
+    update_watches(
+        &mut watcher,
+        &mut watched,
+        watch_paths(args, &config, &current_location, &template_layers),
+    );
+
+    if let Err(e) = render(args, &config, &current_location, None) {
+        error!("渲染失败: {:?}", e);
+    }
+
+    loop {
+        info!("正在监视修改，按 Ctrl+C 退出");
+
+        let (scope, reload) = loop {
+            let mut scope = None;
+            let mut reload = false;
+            for path in next_changes(&rx)? {
+                let Some((path_scope, path_reload)) =
+                    affected(args, &config, &current_location, &template_layers, &path)
+                else {
+                    continue;
+                };
+                debug!("检测到修改: {}", path.display());
+                reload |= path_reload;
+                scope = match (scope, path_scope) {
+                    (Some(Scope::Day(a)), Scope::Day(b)) if a == b => Some(Scope::Day(a)),
+                    (None, path_scope) => Some(path_scope),
+                    _ => Some(Scope::All),
+                };
+            }
+            if let Some(scope) = scope {
+                break (scope, reload);
+            }
+        };
+
+        if reload {
+            match load_config(Path::new(".")) {
+                Ok(Some((new_config, new_location))) => {
+                    info!("已重新加载配置文件");
+                    config = new_config;
+                    current_location = new_location;
+                }
+                Ok(None) => warn!("找不到配置文件，继续使用原有配置"),
+                Err(e) => {
+                    error!("重新加载配置文件失败，继续使用原有配置: {:?}", e);
+                    continue;
+                }
+            }
+        }
+
+        let only_day = match &scope {
+            Scope::All => None,
+            Scope::Day(day_name) => Some(day_name.as_str()),
+        };
+        info!("重新渲染: {}", only_day.unwrap_or("全部"));
+        // 编译失败时不会覆盖已有的输出文件
+        match render(args, &config, &current_location, only_day) {
+            Ok(()) => info!("重新渲染完成"),
+            Err(e) => error!("渲染失败，保留上次的输出: {:?}", e),
+        }
+
+        // 模板的继承关系可能已经改变
+        match resolve::template_layers(&config, &args.target) {
+            Ok(layers) => template_layers = layers,
+            Err(e) => warn!("重新查找模板失败，继续监视原有模板: {:?}", e),
+        }
+        // 新建的 img、sample 目录或模板继承关系的变化需要更新监视的路径
+        update_watches(
+            &mut watcher,
+            &mut watched,
+            watch_paths(args, &config, &current_location, &template_layers),
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use crate::dmk::{compile_std, find_std};
+use crate::prelude::*;
+use crate::test::{
+    ProblemStatus, TestCaseStatus, compile, compile_chk, create_or_clear_dir, run_test_case,
+    validate_output,
+};
+use crate::utils::compile::{build_compile_cmd, build_run_cmd};
+use clap::{Args, ValueEnum};
+use indicatif::ProgressBar;
+use std::fmt;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum ShrinkMode {
+    /// 按行删除
+    #[default]
+    Line,
+    /// 按空白分隔的单词删除
+    Token,
+}
+
+impl fmt::Display for ShrinkMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShrinkMode::Line => write!(f, "line"),
+            ShrinkMode::Token => write!(f, "token"),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[command(version)]
+pub struct ShrinkArgs {
+    /// 错误解法，可以是配置中 tests 的名称或源文件路径
+    #[arg(required = true)]
+    pub solution: String,
+
+    /// 使错误解法出错的输入文件
+    #[arg(required = true)]
+    pub input: PathBuf,
+
+    /// 输入校验器源文件，缩小过程中只保留能通过校验的输入
+    #[arg(long)]
+    pub validator: Option<PathBuf>,
+
+    /// 删除粒度
+    #[arg(long, value_enum, default_value_t = ShrinkMode::Line)]
+    pub mode: ShrinkMode,
+
+    /// 最多运行次数
+    #[arg(long, default_value_t = 1000)]
+    pub max_runs: usize,
+}
+
+/// 可删除的最小单元：所在行号与内容
+type Unit = (usize, String);
+
+/// 缩小过程中使用的各个程序
+struct Shrinker<'a> {
+    problem: &'a ProblemConfig,
+    work_dir: PathBuf,
+    std_src: PathBuf,
+    std_dir: PathBuf,
+    wrong_src: PathBuf,
+    wrong_dir: PathBuf,
+    validator: Option<(PathBuf, PathBuf)>,
+    spj: Option<PathBuf>,
+    runs: usize,
+}
+
+fn split_units(content: &str, mode: ShrinkMode) -> Vec<Unit> {
+    match mode {
+        ShrinkMode::Line => content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.to_string()))
+            .collect(),
+        ShrinkMode::Token => content
+            .lines()
+            .enumerate()
+            .flat_map(|(i, line)| line.split_whitespace().map(move |t| (i, t.to_string())))
+            .collect(),
+    }
+}
+
+/// 将单元重新拼接为输入文件，同一行的单元以空格分隔
+fn join_units(units: &[Unit]) -> String {
+    let mut content = String::new();
+    let mut last_line = None;
+    for (line, text) in units {
+        match last_line {
+            Some(last) if last == *line => content.push(' '),
+            Some(_) => content.push('\n'),
+            None => {}
+        }
+        content.push_str(text);
+        last_line = Some(*line);
+    }
+    content.push('\n');
+    content
+}
+
+fn resolve_solution(problem: &ProblemConfig, solution: &str) -> Result<PathBuf> {
+    let path = match problem.tests.get(solution) {
+        Some(test) => problem.path.join(&test.path),
+        None => PathBuf::from(solution),
+    };
+    if !path.exists() {
+        bail!("未找到错误解法: {}", solution);
+    }
+    Ok(dunce::canonicalize(path)?)
+}
+
+/// 编译输入校验器，返回源文件与程序所在目录
+fn compile_validator(
+    validator_path: &Path,
+    day: &ContestDayConfig,
+    tmp_dir: &Path,
+) -> Result<(PathBuf, PathBuf)> {
+    info!("编译输入校验器: {}", validator_path.display());
+
+    create_or_clear_dir(tmp_dir)?;
+    let src_path = tmp_dir.join(validator_path.file_name().context("校验器路径无效")?);
+    fs::copy(validator_path, &src_path)?;
+
+    if let Some(mut cmd) = build_compile_cmd(&src_path, tmp_dir, "val", &day.compile)? {
+        let output = cmd
+            .current_dir(tmp_dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()?;
+        if !output.status.success() {
+            bail!(
+                "输入校验器编译失败: \n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    } else {
+        fs::copy(
+            &src_path,
+            tmp_dir
+                .join("val")
+                .with_extension(src_path.extension().unwrap_or_default()),
+        )?;
+    }
+
+    Ok((src_path, tmp_dir.to_path_buf()))
+}
+
+impl Shrinker<'_> {
+    fn candidate_path(&self) -> PathBuf {
+        self.work_dir.join("cur.in")
+    }
+
+    fn answer_path(&self) -> PathBuf {
+        self.work_dir.join("cur.ans")
+    }
+
+    /// 运行校验器，判断输入是否合法
+    fn validate_input(&self, input_path: &Path) -> Result<bool> {
+        let Some((src_path, dir)) = &self.validator else {
+            return Ok(true);
+        };
+        let mut cmd = match build_run_cmd(src_path, dir, "val")? {
+            Some(cmd) => cmd,
+            None => Command::new(dir.join("val")),
+        };
+        let status = cmd
+            .current_dir(dir)
+            .stdin(fs::File::open(input_path)?)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        Ok(status.success())
+    }
+
+    fn output_path(&self, dir: &Path) -> PathBuf {
+        if self.problem.file_io.unwrap_or(true) {
+            dir.join(format!("{}.out", self.problem.name))
+        } else {
+            dir.join(format!("{}.stdout", self.problem.name))
+        }
+    }
+
+    fn run(&self, src_path: &Path, dir: &Path, input_path: &Path) -> Result<TestCaseStatus> {
+        Ok(run_test_case(
+            src_path,
+            &dir.join(&self.problem.name),
+            &self.problem.name,
+            input_path,
+            (self.problem.time_limit * 1000.0) as u128,
+            self.problem.memory_limit.as_u64(),
+            self.problem.file_io.unwrap_or(true),
+        )?
+        .0)
+    }
+
+    /// 判断在给定输入上错误解法与标程的结果是否仍然不同
+    fn still_fails(&mut self, content: &str) -> Result<bool> {
+        self.runs += 1;
+
+        let input_path = self.candidate_path();
+        fs::write(&input_path, content)?;
+
+        if !self.validate_input(&input_path)? {
+            debug!("输入未通过校验");
+            return Ok(false);
+        }
+
+        // 标程无法正常运行的输入视为非法输入
+        let output_path = self.output_path(&self.std_dir);
+        let _ = fs::remove_file(&output_path);
+        if self.run(&self.std_src, &self.std_dir, &input_path)? != TestCaseStatus::Running
+            || !output_path.exists()
+        {
+            debug!("标程在输入上运行失败");
+            return Ok(false);
+        }
+        fs::copy(&output_path, self.answer_path())?;
+
+        let _ = fs::remove_file(self.output_path(&self.wrong_dir));
+        let status = match self.run(&self.wrong_src, &self.wrong_dir, &input_path)? {
+            TestCaseStatus::Running => validate_output(
+                &self.wrong_dir,
+                &self.problem.name,
+                &self.answer_path(),
+                self.problem.file_io.unwrap_or(true),
+                self.spj.clone(),
+            )?,
+            status => status,
+        };
+        debug!("错误解法结果: {:?}", status);
+
+        Ok(match status {
+            TestCaseStatus::AC | TestCaseStatus::UKE => false,
+            TestCaseStatus::PC(score) => score < 100.0,
+            _ => true,
+        })
+    }
+}
+
+pub fn main(args: ShrinkArgs) -> Result<()> {
+    let config = get_context().config.as_ref().context("找不到配置文件")?;
+
+    let CurrentLocation::Problem(ref day_name, ref problem_name) = config.1 else {
+        bail!("shrink 命令只能在题目目录下运行");
+    };
+    let day = config
+        .0
+        .subconfig
+        .get(day_name)
+        .context(format!("无法获取天配置: {}", day_name))?;
+    let problem = day
+        .subconfig
+        .get(problem_name)
+        .context(format!("无法获取题目配置: {}/{}", day_name, problem_name))?;
+
+    let original = fs::read_to_string(&args.input)
+        .with_context(|| format!("无法读取输入文件: {}", args.input.display()))?;
+
+    let work_dir = problem.path.join("tmp").join("shrink");
+    create_or_clear_dir(&work_dir)?;
+
+    // 编译标程、错误解法与校验器
+    let std_path = find_std(problem)?;
+    let std_dir = work_dir.join("std");
+    compile_std(&std_path, problem, &day.compile, &std_dir)?;
+    let std_src = std_dir.join(std_path.file_name().unwrap());
+
+    let wrong_path = resolve_solution(problem, &args.solution)?;
+    info!("错误解法: {}", wrong_path.display());
+    let wrong_dir = work_dir.join("wrong");
+    create_or_clear_dir(&wrong_dir)?;
+    let wrong_src = wrong_dir.join(wrong_path.file_name().unwrap());
+    fs::copy(&wrong_path, &wrong_src)?;
+    let mut status = ProblemStatus::Compiling;
+    compile(day, problem, &mut status, &wrong_dir, &wrong_src)?;
+    if status != ProblemStatus::Compiled {
+        bail!("错误解法编译失败");
+    }
+
+    let validator = args
+        .validator
+        .as_deref()
+        .map(|path| compile_validator(path, day, &work_dir.join("validator")))
+        .transpose()?;
+
+    let spj = if problem.use_chk.unwrap_or(false) {
+        Some(compile_chk(problem)?)
+    } else {
+        None
+    };
+
+    let mut shrinker = Shrinker {
+        problem,
+        work_dir: work_dir.clone(),
+        std_src,
+        std_dir,
+        wrong_src,
+        wrong_dir,
+        validator,
+        spj,
+        runs: 0,
+    };
+
+    if !shrinker.still_fails(&original)? {
+        bail!("在原始输入上错误解法与标程结果一致（或输入非法），无法缩小");
+    }
+
+    let mut units = split_units(&original, args.mode);
+    info!("开始缩小输入，共 {} 个单元（{}）", units.len(), args.mode);
+
+    let pb = get_context()
+        .multiprogress
+        .add(ProgressBar::new(args.max_runs as u64));
+    pb.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("  [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+
+    // 类似 ddmin：每轮尝试删除一块连续单元，失败则将块划分得更细
+    let mut parts = 2;
+    'outer: while units.len() > 1 {
+        let chunk = units.len().div_ceil(parts);
+        let mut reduced = false;
+
+        for start in (0..units.len()).step_by(chunk) {
+            if shrinker.runs >= args.max_runs {
+                warn!("达到最大运行次数 {}，停止缩小", args.max_runs);
+                break 'outer;
+            }
+
+            let end = (start + chunk).min(units.len());
+            let candidate: Vec<Unit> = units[..start]
+                .iter()
+                .chain(&units[end..])
+                .cloned()
+                .collect();
+
+            pb.set_message(format!("当前 {} 个单元", units.len()));
+            let fails = shrinker.still_fails(&join_units(&candidate))?;
+            pb.set_position(shrinker.runs as u64);
+
+            if fails {
+                units = candidate;
+                parts = (parts - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if parts >= units.len() {
+                break;
+            }
+            parts = (parts * 2).min(units.len());
+        }
+    }
+
+    pb.finish_and_clear();
+
+    // 重新运行一次最终结果，保证答案文件与输入对应
+    let result = join_units(&units);
+    if !shrinker.still_fails(&result)? {
+        bail!("最终结果无法复现错误，程序结果可能不稳定");
+    }
+
+    let min_input = work_dir.join("min.in");
+    let min_answer = work_dir.join("min.ans");
+    fs::copy(shrinker.candidate_path(), &min_input)?;
+    fs::copy(shrinker.answer_path(), &min_answer)?;
+
+    info!(
+        "缩小完成：{} 字节 -> {} 字节，共运行 {} 次",
+        original.len(),
+        result.len(),
+        shrinker.runs
+    );
+    info!("输入: {}", min_input.display());
+    info!("答案: {}", min_answer.display());
+
+    Ok(())
+}
@@ -69,14 +69,14 @@ pub struct ProblemTestResult {
 #[command(version)]
 pub struct TestArgs {}
 
-fn create_or_clear_dir(path: &Path) -> Result<(), std::io::Error> {
+pub(crate) fn create_or_clear_dir(path: &Path) -> Result<(), std::io::Error> {
     if path.exists() {
         fs::remove_dir_all(path)?;
     }
     fs::create_dir_all(path)
 }
 
-fn run_test_case(
+pub(crate) fn run_test_case(
     src_path: &Path,
     program_path: &Path,
     problem_name: &str,
@@ -209,7 +209,7 @@ fn run_test_case(
     Ok(result)
 }
 
-fn validate_output(
+pub(crate) fn validate_output(
     program_dir: &Path,
     problem_name: &str,
     answer_path: &Path,
@@ -288,7 +288,25 @@ fn validate_output(
     }
 }
 
-fn check_test_case(test_case: &TestCase, actual_score: u32) -> bool {
+/// 测试点的得分，部分分按比例折算
+pub(crate) fn case_score(status: TestCaseStatus, max_score: u32) -> u32 {
+    match status {
+        TestCaseStatus::AC => max_score,
+        TestCaseStatus::PC(partial) => ((partial / 100.0) * (max_score as f64)).round() as u32,
+        _ => 0,
+    }
+}
+
+/// 按子任务的评分方法汇总其中各测试点的得分
+pub(crate) fn subtask_score(policy: ScorePolicy, scores: &[u32]) -> u32 {
+    match policy {
+        ScorePolicy::Sum => scores.iter().sum(),
+        ScorePolicy::Max => *scores.iter().max().unwrap_or(&0),
+        ScorePolicy::Min => *scores.iter().min().unwrap_or(&0),
+    }
+}
+
+pub(crate) fn check_test_case(test_case: &TestCase, actual_score: u32) -> bool {
     let conditions = match &test_case.expected {
         ExpectedScore::Single(cond) => vec![cond.clone()],
         ExpectedScore::Multiple(conds) => conds.clone(),
@@ -462,33 +480,10 @@ pub fn main(_: TestArgs) -> Result<()> {
             {
                 info!("使用自定义 chk 设置: {}", use_chk);
 
-                let compile_pb = get_context().multiprogress.add(ProgressBar::new_spinner());
-                compile_pb.enable_steady_tick(Duration::from_millis(100));
-                compile_pb.set_message(format!("编译 {} 题目的 spj", problem_config.name));
-
-                let chk_path = problem_config.path.join("data").join("chk").join("chk.cpp");
-                if !chk_path.exists() {
-                    warn!("chk 文件不存在，跳过测试此题目");
-                    continue;
-                }
-
-                let compile_output = Command::new("g++")
-                    .arg("-o")
-                    .arg(problem_config.path.join("data").join("chk").join("chk"))
-                    .arg(&chk_path)
-                    .arg("-O2")
-                    .arg("-std=c++23")
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::piped())
-                    .output()?;
-                if !compile_output.status.success() {
-                    warn!(
-                        "chk 编译失败，跳过测试此题目: \n{}",
-                        String::from_utf8_lossy(&compile_output.stderr)
-                    );
+                if let Err(e) = compile_chk(problem_config) {
+                    warn!("{}，跳过测试此题目", e);
                     continue;
                 }
-                compile_pb.finish_and_clear();
             }
 
             let test_pb = get_context()
@@ -620,13 +615,7 @@ pub fn main(_: TestArgs) -> Result<()> {
 
                         info!("测试点结果: {:?}", case_status);
 
-                        let earned_score = match case_status {
-                            TestCaseStatus::AC => case.score,
-                            TestCaseStatus::PC(partial) => {
-                                ((partial / 100.0) * (case.score as f64)).round() as u32
-                            }
-                            _ => 0,
-                        };
+                        let earned_score = case_score(case_status, case.score);
                         subtask_scores
                             .get_mut(&case.subtask)
                             .context("不存在指定的 Subtask")?
@@ -673,11 +662,7 @@ pub fn main(_: TestArgs) -> Result<()> {
                     for (id, subtask) in &problem_config.subtasks {
                         let scores = &subtask_scores[id];
 
-                        let subtask_score = match subtask.policy {
-                            ScorePolicy::Sum => scores.iter().sum(),
-                            ScorePolicy::Max => *scores.iter().max().unwrap_or(&0),
-                            ScorePolicy::Min => *scores.iter().min().unwrap_or(&0),
-                        };
+                        let subtask_score = subtask_score(subtask.policy, scores);
 
                         info!(
                             "Subtask #{} 得分 {}/{}",
@@ -772,7 +757,41 @@ pub fn main(_: TestArgs) -> Result<()> {
     Ok(())
 }
 
-fn compile(
+/// 编译题目的 spj，返回可执行文件路径
+pub(crate) fn compile_chk(problem_config: &ProblemConfig) -> Result<PathBuf> {
+    let compile_pb = get_context().multiprogress.add(ProgressBar::new_spinner());
+    compile_pb.enable_steady_tick(Duration::from_millis(100));
+    compile_pb.set_message(format!("编译 {} 题目的 spj", problem_config.name));
+
+    let chk_dir = problem_config.path.join("data").join("chk");
+    let chk_path = chk_dir.join("chk.cpp");
+    if !chk_path.exists() {
+        compile_pb.finish_and_clear();
+        bail!("chk 文件不存在");
+    }
+
+    let compile_output = Command::new("g++")
+        .arg("-o")
+        .arg(chk_dir.join("chk"))
+        .arg(&chk_path)
+        .arg("-O2")
+        .arg("-std=c++23")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()?;
+    compile_pb.finish_and_clear();
+
+    if !compile_output.status.success() {
+        bail!(
+            "chk 编译失败: \n{}",
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    Ok(chk_dir.join("chk"))
+}
+
+pub(crate) fn compile(
     day_config: &ContestDayConfig,
     problem_config: &ProblemConfig,
     problem_status: &mut ProblemStatus,
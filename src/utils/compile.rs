@@ -173,3 +173,30 @@ pub fn build_run_cmd(
         Ok(None)
     }
 }
+
+/// 生成 sanitizer 编译配置
+///
+/// 在 C/C++ 的编译选项后追加 `-fsanitize=address,undefined`，
+/// 并去掉与 AddressSanitizer 冲突的 `-static`，其余语言保持不变。
+pub fn sanitizer_profile(compile_args: &HashMap<String, String>) -> HashMap<String, String> {
+    compile_args
+        .iter()
+        .map(|(lang, args)| {
+            let args = if lang == "cpp" || lang == "c" {
+                let mut parts: Vec<&str> = args
+                    .split_whitespace()
+                    .filter(|arg| *arg != "-static")
+                    .collect();
+                parts.extend([
+                    "-g",
+                    "-fno-omit-frame-pointer",
+                    "-fsanitize=address,undefined",
+                ]);
+                parts.join(" ")
+            } else {
+                args.clone()
+            };
+            (lang.clone(), args)
+        })
+        .collect()
+}